@@ -1,14 +1,19 @@
-use crate::commands::command_trait;
 use crate::commands::command_trait::CommandInfo;
+use crate::commands::task_search::{fuzzy_select, SelectItem};
+use crate::error::{AppError, AppResult};
+use crate::inputs::arguments::Arguments;
+use crate::inputs::due_date;
 use crate::inputs::error_handling::InputErrorHandling;
-use crate::storage_handler::StorageHandler;
+use crate::inputs::notes;
+use crate::inputs::recurrence;
+use crate::inputs::tags;
+use crate::storage_handler::recurrence::Recurrence;
+use crate::storage_handler::{Priority, StorageHandler, Task};
 use crate::Command;
+use chrono::NaiveDate;
 use dialoguer::console::Term;
 use dialoguer::theme::ColorfulTheme;
 use dialoguer::{Confirm, Input, Select};
-use std::error::Error;
-use std::io::Result;
-use std::ops::{Add, Deref};
 
 #[derive(Copy, Clone)]
 enum AddAction {
@@ -24,6 +29,7 @@ pub struct AddCommand {
     description: String,
     usage: String,
     add_action: Option<AddAction>,
+    arguments: Arguments,
     storage_handler: StorageHandler,
 }
 
@@ -32,21 +38,245 @@ impl AddCommand {
     /// values provided. The storage handler is never reinitialized
     /// and only initialized on Command creation
     pub fn new() -> AddCommand {
+        AddCommand::with_storage(StorageHandler::new())
+    }
+
+    /// Creates a new instance of the add command
+    /// that uses the given storage handler
+    pub fn with_storage(storage_handler: StorageHandler) -> AddCommand {
         AddCommand {
             title: String::from("Add Command"),
             description: String::from("Makes it possible to add new task groups or single tasks"),
-            usage: String::from("just enter the command"),
+            usage: String::from(
+                "add [title] [+tag...] [--group <group>] [--due <date>] [--priority <priority>] [--repeat <rule>] [--notes <text|->] [--parent <id>] [--depends-on <id,...>] | add --new-group <name> | or just enter the command",
+            ),
             add_action: None,
-            storage_handler: StorageHandler::new(),
+            arguments: Arguments::default(),
+            storage_handler,
+        }
+    }
+
+    /// Parses the raw command line arguments and stores them in the command.
+    /// If a title or a group name is passed, no prompts are opened
+    pub fn with_arguments(mut self, raw: Vec<String>) -> AddCommand {
+        self.arguments = Arguments::parse(raw, &[]);
+        self
+    }
+
+    /// Checks if the passed group exists. If not,
+    /// a not found error is returned
+    fn validate_group(&mut self, group: &Option<String>) -> AppResult<()> {
+        match group {
+            Some(name) if !self.storage_handler.get_all_groups()?.contains(name) => Err(
+                AppError::NotFound(format!("The group {} does not exist", name)),
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    /// Gets the parent task whose ID is passed as argument.
+    /// If there is no task with the ID, a not found error is returned
+    fn get_parent_argument(&mut self) -> AppResult<Option<Task>> {
+        let value = match self.arguments.flag_value("parent") {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        let id = value
+            .parse::<u64>()
+            .map_err(|_| AppError::Validation(format!("{} is not a valid task ID", value)))?;
+        match self.storage_handler.get_task_by_id(id)? {
+            Some(task) => Ok(Some(task)),
+            None => Err(AppError::NotFound(format!(
+                "There is no task with ID {}",
+                id
+            ))),
         }
     }
 
+    /// Opens a prompt for selecting the parent task from
+    /// all tasks of the group. Nothing is prompted if the group has no tasks
+    fn open_parent_prompt(&mut self, group: Option<&str>) -> AppResult<Option<u64>> {
+        let tasks = self.storage_handler.get_tasks_by_group(group)?;
+        if tasks.is_empty() {
+            return Ok(None);
+        }
+        let mut items = vec!["❌ no parent".to_string()];
+        items.extend(tasks.iter().map(|task| task.title.clone()));
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Parent task")
+            .items(&items)
+            .default(0)
+            .interact_on_opt(&Term::stderr());
+        match self.handle_select_error(selection)? {
+            0 => Ok(None),
+            val => Ok(Some(tasks[val - 1].id)),
+        }
+    }
+
+    /// Parses the due date that is passed as argument.
+    /// Supports the same formats as the due date prompt
+    fn get_due_argument(&mut self) -> AppResult<Option<NaiveDate>> {
+        match self.arguments.flag_value("due") {
+            Some(value) => Ok(Some(due_date::parse_due_date(&value, due_date::today())?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Parses the recurrence rule that is passed as argument
+    fn get_repeat_argument(&mut self) -> AppResult<Option<Recurrence>> {
+        match self.arguments.flag_value("repeat") {
+            Some(value) => Ok(Some(Recurrence::parse(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Sets the recurrence of the task. Scheduled tasks without
+    /// a due date are due on their first occurrence
+    fn set_recurrence(&mut self, task: &mut Task, recurrence: Option<Recurrence>) {
+        if task.due.is_none() {
+            task.due = recurrence
+                .as_ref()
+                .and_then(|recurrence| recurrence.first_due(due_date::today()));
+        }
+        task.recurrence = recurrence;
+    }
+
+    /// Creates a new task from the title input. Words starting with a +
+    /// are removed from the title and added as tags to the task
+    fn create_task(&mut self, input: &str, group: Option<String>) -> AppResult<Task> {
+        let (title, tags) = tags::extract_tags(input);
+        if title.is_empty() {
+            return Err(AppError::Validation(
+                "The title must not be empty".to_string(),
+            ));
+        }
+        let mut task = Task::new(title, group);
+        task.tags = tags;
+        Ok(task)
+    }
+
+    /// Opens a prompt for additional tags of the task.
+    /// Tags are entered as +tag and separated by spaces
+    fn open_tags_prompt(&mut self, task: &mut Task) -> AppResult<()> {
+        let input: String = Input::new()
+            .with_prompt("Tags (e.g. +backend +ci; empty for none)")
+            .allow_empty(true)
+            .interact_text()?;
+        let (rest, new_tags) = tags::extract_tags(&input);
+        if !rest.is_empty() {
+            return Err(AppError::Validation(format!(
+                "{} is not a valid tag. Use +tag",
+                rest
+            )));
+        }
+        for tag in new_tags {
+            if !task.tags.contains(&tag) {
+                task.tags.push(tag);
+            }
+        }
+        Ok(())
+    }
+
+    /// Asks if notes should be added to the task
+    /// and opens the notes prompt if confirmed
+    fn open_notes_prompt(&mut self) -> AppResult<Option<String>> {
+        let add_notes = Confirm::new()
+            .with_prompt("Add notes?")
+            .default(false)
+            .interact()?;
+        if !add_notes {
+            return Ok(None);
+        }
+        notes::prompt_notes(None)
+    }
+
+    /// Parses the priority that is passed as argument
+    fn get_priority_argument(&mut self) -> AppResult<Priority> {
+        match self.arguments.flag_value("priority") {
+            Some(value) => Priority::parse(&value),
+            None => Ok(Priority::None),
+        }
+    }
+
+    /// Opens a prompt for selecting the priority of the task
+    fn open_priority_prompt(&mut self) -> AppResult<Priority> {
+        let items = Priority::ALL
+            .iter()
+            .map(|priority| format!("{} {}", priority.name(), priority.marker()))
+            .collect::<Vec<String>>();
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Priority")
+            .items(&items)
+            .default(0)
+            .interact_on_opt(&Term::stderr());
+        let val = self.handle_select_error(selection)?;
+        Ok(Priority::ALL[val])
+    }
+
+    /// Checks if the name can be used for a new task group
+    fn validate_new_group(&mut self, name: &str) -> AppResult<()> {
+        if name.trim().is_empty() {
+            return Err(AppError::Validation(
+                "The group name must not be empty".to_string(),
+            ));
+        }
+        if self
+            .storage_handler
+            .get_all_groups()?
+            .iter()
+            .any(|group| group == name)
+        {
+            return Err(AppError::Validation(format!(
+                "The group {} already exists",
+                name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Adds a new task or task group only by the passed arguments.
+    /// Returns false if the arguments do not contain enough
+    /// information and the prompts have to be used instead
+    fn add_by_arguments(&mut self) -> AppResult<bool> {
+        if let Some(name) = self.arguments.flag_value("new-group") {
+            self.validate_new_group(&name)?;
+            self.storage_handler.add_task_group(name)?;
+            return Ok(true);
+        }
+        if self.arguments.positional().is_empty() {
+            if self.arguments.has_flag("group") {
+                self.add_action = Some(AddAction::SingleTask);
+            }
+            return Ok(false);
+        }
+        let title = self.arguments.positional().join(" ");
+        let parent = self.get_parent_argument()?;
+        let group = match (self.arguments.flag_value("group"), &parent) {
+            (None, Some(parent)) => parent.group.clone(),
+            (group, _) => group,
+        };
+        self.validate_group(&group)?;
+        let mut task = self.create_task(&title, group)?;
+        task.parent = parent.map(|parent| parent.id);
+        task.depends_on = self.arguments.id_list("depends-on")?;
+        self.storage_handler.validate_task_ids(&task.depends_on)?;
+        task.due = self.get_due_argument()?;
+        task.priority = self.get_priority_argument()?;
+        let recurrence = self.get_repeat_argument()?;
+        self.set_recurrence(&mut task, recurrence);
+        if let Some(value) = self.arguments.flag_value("notes") {
+            task.notes = notes::read_notes_argument(&value)?;
+        }
+        self.storage_handler.add_single_task(task)?;
+        Ok(true)
+    }
+
     /// Takes an instance of the AddAction enum and sets
     /// the add_action of the object. Furthermore, the command
     /// will be re-executed after this function call.
-    fn submit_add_action_type(&mut self, action: AddAction) {
+    fn submit_add_action_type(&mut self, action: AddAction) -> AppResult<()> {
         self.add_action = Some(action);
-        self.execute();
+        self.execute()
     }
 
     /// Gets an enum instance by the value that has been selected
@@ -61,69 +291,88 @@ impl AddCommand {
 
     /// Opens an prompt for reading the add_action from the user input.
     /// After that the selected value is saved into the objects memory.
-    fn open_add_action_prompt(&mut self) {
-        let items = vec!["Add a new task", "Add a new task group"];
+    fn open_add_action_prompt(&mut self) -> AppResult<()> {
+        let items = ["Add a new task", "Add a new task group"];
         let selection = Select::with_theme(&ColorfulTheme::default())
             .items(&items)
             .default(0)
             .interact_on_opt(&Term::stderr());
-        match self.handle_select_error(selection) {
-            None => {}
-            Some(u) => {
-                let action = self.get_add_action_value_by_input(items[u]);
-                self.submit_add_action_type(action);
-            }
-        }
+        let u = self.handle_select_error(selection)?;
+        let action = self.get_add_action_value_by_input(items[u]);
+        self.submit_add_action_type(action)
     }
 
     /// Opens a prompt for inserting the value for the new
     /// task. If the valze has been submitted a new single
     /// task that is not assigned to any group will be created
-    fn add_single_task(&mut self) {
-        let input: String = Input::new()
-            .with_prompt("Title")
-            .interact_text()
-            .expect("Failed while inserting data");
-        let mut groups = self.storage_handler.get_all_groups();
-        let mut task_group: Option<String> = None;
-        if groups.len() > 0 {
+    fn add_single_task(&mut self) -> AppResult<()> {
+        let input: String = Input::new().with_prompt("Title").interact_text()?;
+        let mut groups = self.storage_handler.get_all_groups()?;
+        let parent = self.get_parent_argument()?;
+        let mut task_group = match &parent {
+            Some(parent) if !self.arguments.has_flag("group") => parent.group.clone(),
+            _ => self.arguments.flag_value("group"),
+        };
+        self.validate_group(&task_group)?;
+        if task_group.is_none() && parent.is_none() && !groups.is_empty() {
             groups.push("❌ no group".to_string());
             groups = groups.into_iter().rev().collect();
-            let selection = Select::with_theme(&ColorfulTheme::default())
-                .items(&groups)
-                .default(0)
-                .interact_on_opt(&Term::stderr());
-            match self.handle_select_error(selection) {
-                None => {},
-                Some(val) => {
-                    if 0 != val {
-                        task_group = Some(groups[val].clone());
-                    }
-                }
+            let items = groups
+                .iter()
+                .map(|group| SelectItem::text(group))
+                .collect::<Vec<SelectItem>>();
+            let selection = fuzzy_select(&items, &Term::stderr());
+            let val = self.handle_select_error(selection)?;
+            if val != 0 {
+                task_group = Some(groups[val].clone());
             }
         }
-        if self.confirm_selection() {
-            return self.storage_handler.add_single_task(input, task_group);
-        }
+        let task_parent = match parent {
+            Some(parent) => Some(parent.id),
+            None => self.open_parent_prompt(task_group.as_deref())?,
+        };
+        let mut task = self.create_task(&input, task_group)?;
+        task.parent = task_parent;
+        task.depends_on = self.arguments.id_list("depends-on")?;
+        self.storage_handler.validate_task_ids(&task.depends_on)?;
+        self.open_tags_prompt(&mut task)?;
+        task.due = match self.arguments.has_flag("due") {
+            true => self.get_due_argument()?,
+            false => due_date::prompt_due_date(None)?,
+        };
+        let recurrence = match self.arguments.has_flag("repeat") {
+            true => self.get_repeat_argument()?,
+            false => recurrence::prompt_recurrence(None)?,
+        };
+        self.set_recurrence(&mut task, recurrence);
+        task.priority = match self.arguments.has_flag("priority") {
+            true => self.get_priority_argument()?,
+            false => self.open_priority_prompt()?,
+        };
+        task.notes = match self.arguments.flag_value("notes") {
+            Some(value) => notes::read_notes_argument(&value)?,
+            None => self.open_notes_prompt()?,
+        };
+        self.confirm_selection()?;
+        self.storage_handler.add_single_task(task)?;
+        Ok(())
     }
 
     /// Opens a prompt for confirming a specific action.
-    /// The result of the confirmation will be returned
-    fn confirm_selection(&mut self) -> bool {
-        Confirm::new().with_prompt("Save?").interact().unwrap()
+    /// Declining the prompt cancels the command
+    fn confirm_selection(&mut self) -> AppResult<()> {
+        let confirmation = Confirm::new().with_prompt("Save?").interact();
+        self.handle_confirm_error(confirmation)
     }
 
     /// Opens a prompt for creating a new
     /// task group. If the action is confirmed
     /// the data will be saved
-    fn add_task_group(&mut self) {
-        let input: String = Input::new()
-            .with_prompt("Group name")
-            .interact_text()
-            .expect("Failed while inserting data");
-        if self.confirm_selection() {
-            return self.storage_handler.add_task_group(input);
-        }
+    fn add_task_group(&mut self) -> AppResult<()> {
+        let input: String = Input::new().with_prompt("Group name").interact_text()?;
+        self.validate_new_group(&input)?;
+        self.confirm_selection()?;
+        self.storage_handler.add_task_group(input)
     }
 }
 
@@ -132,7 +381,10 @@ impl InputErrorHandling for AddCommand {}
 impl Command for AddCommand {
     /// Executes the main code of the command.
     /// This method is called of the command is initialized
-    fn execute(&mut self) {
+    fn execute(&mut self) -> AppResult<()> {
+        if self.add_action.is_none() && self.add_by_arguments()? {
+            return Ok(());
+        }
         if self.add_action.is_none() {
             return self.open_add_action_prompt();
         }
@@ -151,3 +403,33 @@ impl Command for AddCommand {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage_handler::memory_backend::MemoryBackend;
+
+    fn command(raw: &[&str]) -> AddCommand {
+        let storage_handler = StorageHandler::with_backend(Box::new(MemoryBackend::new()));
+        AddCommand::with_storage(storage_handler)
+            .with_arguments(raw.iter().map(|arg| arg.to_string()).collect())
+    }
+
+    #[test]
+    fn adds_the_task_passed_as_arguments() {
+        let mut command = command(&["buy", "milk", "+shop", "--priority", "high"]);
+        command.execute().unwrap();
+        let tasks = command.storage_handler.get_all_tasks().unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].title, "buy milk");
+        assert_eq!(tasks[0].tags, ["shop"]);
+        assert!(tasks[0].priority == Priority::High);
+    }
+
+    #[test]
+    fn refuses_unknown_groups() {
+        let mut command = command(&["buy", "--group", "home"]);
+        assert!(matches!(command.execute(), Err(AppError::NotFound(_))));
+        assert!(command.storage_handler.get_all_tasks().unwrap().is_empty());
+    }
+}
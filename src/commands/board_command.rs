@@ -0,0 +1,77 @@
+use crate::commands::command_trait::{Command, CommandInfo};
+use crate::commands::task_format::format_task;
+use crate::error::AppResult;
+use crate::inputs::arguments::Arguments;
+use crate::inputs::tags::TagFilter;
+use crate::tui;
+use crate::tui::board::{Board, BoardLayout};
+use dialoguer::console::Term;
+
+/// Defines the board command that shows
+/// the tasks as columns of a kanban board
+pub struct BoardCommand {
+    title: String,
+    description: String,
+    usage: String,
+    arguments: Arguments,
+}
+
+impl BoardCommand {
+    /// Creates a new instance of the board command
+    /// with the default values.
+    pub fn new() -> BoardCommand {
+        BoardCommand {
+            title: String::from("Board"),
+            description: String::from(
+                "Shows the tasks as a kanban board with a column per status or group. Cards are moved with the keyboard",
+            ),
+            usage: String::from("board [--by status|group] [+tag...] [-tag...] [--plain]"),
+            arguments: Arguments::default(),
+        }
+    }
+
+    /// Parses the raw command line arguments and stores them in the command.
+    /// If --plain is passed, the columns are printed instead of opened
+    pub fn with_arguments(mut self, raw: Vec<String>) -> BoardCommand {
+        self.arguments = Arguments::parse(raw, &["plain"]);
+        self
+    }
+
+    /// Prints all columns with their tasks as plain text. This is used
+    /// if --plain is passed or the terminal is not interactive
+    fn print_board(&self, board: &Board) {
+        for column in board.columns.iter() {
+            println!("{} ({})", column.title(), column.tasks.len());
+            for task in column.tasks.iter() {
+                println!("{:>4}  {}", task.id, format_task(task, &board.all_tasks));
+            }
+        }
+    }
+}
+
+impl Command for BoardCommand {
+    /// This method is called on command execution.
+    /// Contains the main code of the command
+    fn execute(&mut self) -> AppResult<()> {
+        let layout = match self.arguments.flag_value("by") {
+            Some(value) => BoardLayout::parse(&value)?,
+            None => BoardLayout::Status,
+        };
+        let filter = TagFilter::parse(self.arguments.positional())?;
+        let mut board = Board::new(layout, filter)?;
+        if self.arguments.has_flag("plain") || !Term::stdout().is_term() {
+            self.print_board(&board);
+            return Ok(());
+        }
+        tui::run_board(&mut board)
+    }
+
+    /// Gets the base command information
+    fn get_command_info(&mut self) -> CommandInfo {
+        CommandInfo {
+            title: self.title.clone(),
+            description: self.description.clone(),
+            usage: self.usage.clone(),
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use crate::error::AppResult;
+
 /// Defines the base information of a command
 /// that needs to be delivered
 pub struct CommandInfo {
@@ -9,6 +11,6 @@ pub struct CommandInfo {
 /// Defines methods that need to be implemented
 /// by a command to ensure that the command is working properly
 pub trait Command {
-    fn execute(&mut self);
+    fn execute(&mut self) -> AppResult<()>;
     fn get_command_info(&mut self) -> CommandInfo;
 }
@@ -1,75 +1,202 @@
-use dialoguer::console::Term;
-use dialoguer::Select;
-use dialoguer::theme::ColorfulTheme;
-use serde::de::Unexpected::Str;
-use crate::Command;
 use crate::commands::command_trait::CommandInfo;
+use crate::commands::group_command::GroupCommand;
+use crate::commands::task_search::{fuzzy_select, SelectItem};
+use crate::error::{AppError, AppResult};
+use crate::inputs::arguments::Arguments;
 use crate::inputs::error_handling::InputErrorHandling;
 use crate::storage_handler::{StorageHandler, Task};
+use crate::Command;
+use dialoguer::console::Term;
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::Select;
+use std::collections::HashSet;
+
+/// Defines what happens with the subtasks of deleted tasks
+pub enum ChildAction {
+    Cascade,
+    Reparent,
+}
 
 /// Defines the basic structure of the delete command
 pub struct DeleteCommand {
     title: String,
     description: String,
     usage: String,
-    storage_handler: StorageHandler
+    arguments: Arguments,
+    storage_handler: StorageHandler,
 }
 
 impl InputErrorHandling for DeleteCommand {}
 
 impl DeleteCommand {
-
     /// Creates a new instance of the delete command
     /// and initializes the storage handler in it.
     pub fn new() -> DeleteCommand {
+        DeleteCommand::with_storage(StorageHandler::new())
+    }
+
+    /// Creates a new instance of the delete command
+    /// that uses the given storage handler
+    pub fn with_storage(storage_handler: StorageHandler) -> DeleteCommand {
         DeleteCommand {
             title: String::from("Delete command"),
             description: String::from("Deletes specific tasks or task groups"),
-            usage: String::from("Just type the command"),
-            storage_handler: StorageHandler::new()
+            usage: String::from("delete [id...] [--cascade|--reparent] or just type the command"),
+            arguments: Arguments::default(),
+            storage_handler,
         }
     }
-}
 
-impl Command for DeleteCommand {
+    /// Parses the raw command line arguments and stores them in the command.
+    /// If IDs are passed, the tasks are deleted without any prompt
+    pub fn with_arguments(mut self, raw: Vec<String>) -> DeleteCommand {
+        self.arguments = Arguments::parse(raw, &["cascade", "reparent"]);
+        self
+    }
 
-    /// This method is called on command execution.
-    /// Contains the main code of the command
-    fn execute(&mut self) {
-        let mut single_tasks = self
-            .storage_handler
-            .get_all_tasks();
-
-        let items = (&single_tasks)
-            .into_iter()
-            .map(|x| {
-                if x.group.is_some() {
-                    return x.title.clone() + " (" + x.group.as_ref().unwrap().as_str() + ")";
-                }
-                return x.title.clone();
-            })
-            .collect::<Vec<String>>();
-
-        if items.len() > 0 {
-            let selection = Select::with_theme(&ColorfulTheme::default())
-                .items(&items)
-                .default(0)
-                .interact_on_opt(&Term::stderr());
-
-            match self.handle_select_error(selection) {
-                None => println!("An error occurred while selecting"),
-                Some(val) => {
-                    let mut new_values = vec![];
-                    for i in 0..single_tasks.len() {
-                        if i != val {
-                            new_values.push(single_tasks[i].clone());
+    /// Gets what should happen with the subtasks of the deleted tasks.
+    /// The action is taken from the arguments or selected in a prompt
+    fn get_child_action(&mut self) -> AppResult<ChildAction> {
+        if self.arguments.has_flag("cascade") {
+            return Ok(ChildAction::Cascade);
+        }
+        if self.arguments.has_flag("reparent") {
+            return Ok(ChildAction::Reparent);
+        }
+        if !Term::stderr().is_term() {
+            return Err(AppError::Validation(
+                "The task has subtasks. Pass --cascade or --reparent".to_string(),
+            ));
+        }
+        let items = ["Delete the subtasks too", "Move the subtasks one level up"];
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("The task has subtasks")
+            .items(&items)
+            .default(0)
+            .interact_on_opt(&Term::stderr());
+        match self.handle_select_error(selection)? {
+            0 => Ok(ChildAction::Cascade),
+            _ => Ok(ChildAction::Reparent),
+        }
+    }
+
+    /// Deletes the tasks with the given IDs. If they have subtasks,
+    /// the subtasks are deleted too or moved to the parent of the deleted task
+    fn delete_with_children(&mut self, ids: &[u64]) -> AppResult<()> {
+        let tasks = self.storage_handler.get_all_tasks()?;
+        let deleted = ids.iter().copied().collect::<HashSet<u64>>();
+        let has_children = tasks.iter().any(|task| {
+            task.parent.is_some_and(|parent| deleted.contains(&parent))
+                && !deleted.contains(&task.id)
+        });
+        if !has_children {
+            return self.storage_handler.delete_tasks(ids);
+        }
+        let action = self.get_child_action()?;
+        self.delete_with_action(ids, action)
+    }
+
+    /// Deletes the tasks with the given IDs and applies
+    /// the action to all of their subtasks
+    pub fn delete_with_action(&mut self, ids: &[u64], action: ChildAction) -> AppResult<()> {
+        let tasks = self.storage_handler.get_all_tasks()?;
+        let mut deleted = ids.iter().copied().collect::<HashSet<u64>>();
+        match action {
+            ChildAction::Cascade => {
+                let mut changed = true;
+                while changed {
+                    changed = false;
+                    for task in tasks.iter() {
+                        if task.parent.is_some_and(|parent| deleted.contains(&parent))
+                            && deleted.insert(task.id)
+                        {
+                            changed = true;
                         }
                     }
-                    self.storage_handler.write_task_data(new_values);
                 }
             }
+            ChildAction::Reparent => {
+                let moved = tasks
+                    .iter()
+                    .filter(|task| !deleted.contains(&task.id))
+                    .filter(|task| task.parent.is_some_and(|parent| deleted.contains(&parent)))
+                    .map(|task| Task {
+                        parent: self.get_remaining_parent(&tasks, &deleted, task.parent),
+                        ..task.clone()
+                    })
+                    .collect::<Vec<Task>>();
+                self.storage_handler.write_task_data(moved)?;
+            }
+        }
+        self.storage_handler
+            .delete_tasks(&deleted.into_iter().collect::<Vec<u64>>())
+    }
+
+    /// Walks up the parents until a task is found that is not deleted
+    fn get_remaining_parent(
+        &self,
+        tasks: &[Task],
+        deleted: &HashSet<u64>,
+        mut parent: Option<u64>,
+    ) -> Option<u64> {
+        while let Some(id) = parent {
+            if !deleted.contains(&id) {
+                return Some(id);
+            }
+            parent = tasks
+                .iter()
+                .find(|task| task.id == id)
+                .and_then(|task| task.parent);
+        }
+        None
+    }
+
+    /// Deletes all tasks whose IDs are passed as arguments.
+    /// Nothing is deleted if a single ID is invalid or unknown
+    fn delete_by_arguments(&mut self) -> AppResult<()> {
+        let ids = self.arguments.ids()?;
+        let tasks = self.storage_handler.get_all_tasks()?;
+        for id in ids.iter() {
+            if !self.storage_handler.id_exists_in_vec(&tasks, *id) {
+                return Err(AppError::NotFound(format!(
+                    "There is no task with ID {}",
+                    id
+                )));
+            }
+        }
+        self.delete_with_children(&ids)
+    }
+}
+
+impl Command for DeleteCommand {
+    /// This method is called on command execution.
+    /// Contains the main code of the command
+    fn execute(&mut self) -> AppResult<()> {
+        if !self.arguments.positional().is_empty() {
+            return self.delete_by_arguments();
+        }
+        let groups = self.storage_handler.get_all_groups()?;
+        let single_tasks = self.storage_handler.get_all_tasks()?;
+
+        let mut items = groups
+            .iter()
+            .map(|group| SelectItem::text(&("📁 ".to_string() + group)))
+            .collect::<Vec<SelectItem>>();
+        items.extend(single_tasks.iter().map(|x| match &x.group {
+            Some(group) => SelectItem::task(x, x.title.clone() + " (" + group + ")"),
+            None => SelectItem::task(x, x.title.clone()),
+        }));
+
+        if !items.is_empty() {
+            let selection = fuzzy_select(&items, &Term::stderr());
+
+            match self.handle_select_error(selection)? {
+                val if val < groups.len() => GroupCommand::new().open_delete_prompt(&groups[val]),
+                val => self.delete_with_children(&[single_tasks[val - groups.len()].id]),
+            }
         } else {
             println!("No tasks given");
+            Ok(())
         }
     }
 
@@ -78,7 +205,7 @@ impl Command for DeleteCommand {
         CommandInfo {
             title: self.title.clone(),
             description: self.description.clone(),
-            usage: self.usage.clone()
+            usage: self.usage.clone(),
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,75 @@
+use crate::commands::command_trait::{Command, CommandInfo};
+use crate::error::{AppError, AppResult};
+use crate::inputs::arguments::Arguments;
+use crate::storage_handler::StorageHandler;
+
+/// Defines the depend command that declares
+/// which tasks have to be finished before a task
+pub struct DependCommand {
+    title: String,
+    description: String,
+    usage: String,
+    arguments: Arguments,
+    storage_handler: StorageHandler,
+}
+
+impl DependCommand {
+    /// Creates a new instance of the depend command
+    /// and initializes the storage handler in it.
+    pub fn new() -> DependCommand {
+        DependCommand::with_storage(StorageHandler::new())
+    }
+
+    /// Creates a new instance of the depend command
+    /// that uses the given storage handler
+    pub fn with_storage(storage_handler: StorageHandler) -> DependCommand {
+        DependCommand {
+            title: String::from("Depend"),
+            description: String::from(
+                "Declares that a task depends on other tasks. With --remove the dependencies are removed",
+            ),
+            usage: String::from("depend <id> <dependency id...> [--remove]"),
+            arguments: Arguments::default(),
+            storage_handler,
+        }
+    }
+
+    /// Parses the raw command line arguments and stores them in the command
+    pub fn with_arguments(mut self, raw: Vec<String>) -> DependCommand {
+        self.arguments = Arguments::parse(raw, &["remove"]);
+        self
+    }
+}
+
+impl Command for DependCommand {
+    /// This method is called on command execution.
+    /// Contains the main code of the command
+    fn execute(&mut self) -> AppResult<()> {
+        let ids = self.arguments.ids()?;
+        if ids.len() < 2 {
+            return Err(AppError::Validation(format!("Usage: {}", self.usage)));
+        }
+        let mut task = self
+            .storage_handler
+            .get_task_by_id(ids[0])?
+            .ok_or_else(|| AppError::NotFound(format!("There is no task with ID {}", ids[0])))?;
+        for id in ids.iter().skip(1) {
+            match self.arguments.has_flag("remove") {
+                true => task.depends_on.retain(|dependency| dependency != id),
+                false if !task.depends_on.contains(id) => task.depends_on.push(*id),
+                false => {}
+            }
+        }
+        self.storage_handler.validate_dependencies(&task)?;
+        self.storage_handler.write_task_data(vec![task])
+    }
+
+    /// Gets the base command information
+    fn get_command_info(&mut self) -> CommandInfo {
+        CommandInfo {
+            title: self.title.clone(),
+            description: self.description.clone(),
+            usage: self.usage.clone(),
+        }
+    }
+}
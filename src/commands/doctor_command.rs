@@ -0,0 +1,93 @@
+use crate::commands::command_trait::{Command, CommandInfo};
+use crate::error::{AppError, AppResult};
+use crate::inputs::arguments::Arguments;
+use crate::inputs::error_handling::InputErrorHandling;
+use crate::storage_handler::json_backend::JsonBackend;
+use crate::storage_handler::repair;
+use dialoguer::console::Term;
+use dialoguer::Confirm;
+
+/// Defines the doctor command that checks
+/// the data.json file and repairs it
+pub struct DoctorCommand {
+    title: String,
+    description: String,
+    usage: String,
+    arguments: Arguments,
+    backend: JsonBackend,
+}
+
+impl InputErrorHandling for DoctorCommand {}
+
+impl DoctorCommand {
+    /// Creates a new instance of the doctor command.
+    /// The command always works on the data.json file directly
+    pub fn new() -> DoctorCommand {
+        DoctorCommand {
+            title: String::from("Doctor"),
+            description: String::from(
+                "Checks the data file for problems and repairs it. The previous file is kept as data.json.bak",
+            ),
+            usage: String::from("doctor [--yes]"),
+            arguments: Arguments::default(),
+            backend: JsonBackend::new(),
+        }
+    }
+
+    /// Parses the raw command line arguments and stores them in the command.
+    /// If --yes is passed, the repaired file is written without confirmation
+    pub fn with_arguments(mut self, raw: Vec<String>) -> DoctorCommand {
+        self.arguments = Arguments::parse(raw, &["yes"]);
+        self
+    }
+
+    /// Offers to repair the data file if loading it failed with the given
+    /// error. If the repair is declined or not possible, the error is returned
+    pub fn recover_from(&mut self, error: AppError) -> AppResult<()> {
+        if !matches!(error, AppError::Parse(_)) || !Term::stderr().is_term() {
+            return Err(error);
+        }
+        eprintln!("ERROR: {}", error);
+        let confirmation = Confirm::new()
+            .with_prompt("The data file seems to be corrupted. Run the doctor now?")
+            .interact();
+        if self.handle_confirm_error(confirmation).is_err() {
+            return Err(error);
+        }
+        self.execute()
+    }
+}
+
+impl Command for DoctorCommand {
+    /// This method is called on command execution.
+    /// Contains the main code of the command
+    fn execute(&mut self) -> AppResult<()> {
+        let raw = self.backend.read_tasks_file()?;
+        let result = repair::check_data(&raw)?;
+        if result.problems.is_empty() {
+            println!("No problems found");
+            return Ok(());
+        }
+        for problem in result.problems.iter() {
+            println!("- {}", problem);
+        }
+        if !self.arguments.has_flag("yes") {
+            let confirmation = Confirm::new()
+                .with_prompt("Write the repaired data file?")
+                .interact();
+            self.handle_confirm_error(confirmation)?;
+        }
+        self.backend.replace_data(result.data)?;
+        println!("The data file has been repaired");
+        Ok(())
+    }
+
+    /// Gets the base command information
+    fn get_command_info(&mut self) -> CommandInfo {
+        CommandInfo {
+            title: self.title.clone(),
+            description: self.description.clone(),
+            usage: self.usage.clone(),
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use crate::commands::command_trait::{Command, CommandInfo};
+use crate::commands::task_format::{print_occurrences, warn_open_blockers};
+use crate::error::{AppError, AppResult};
+use crate::inputs::arguments::Arguments;
+use crate::inputs::due_date;
+use crate::inputs::error_handling::InputErrorHandling;
+use crate::storage_handler::status::Status;
+use crate::storage_handler::{StorageHandler, Task};
+use dialoguer::console::Term;
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::Select;
+
+/// Defines the done command that marks tasks as finished
+pub struct DoneCommand {
+    title: String,
+    description: String,
+    usage: String,
+    arguments: Arguments,
+    storage_handler: StorageHandler,
+}
+
+impl InputErrorHandling for DoneCommand {}
+
+impl DoneCommand {
+    /// Creates a new instance of the done command
+    /// and initializes the storage handler in it.
+    pub fn new() -> DoneCommand {
+        DoneCommand::with_storage(StorageHandler::new())
+    }
+
+    /// Creates a new instance of the done command
+    /// that uses the given storage handler
+    pub fn with_storage(storage_handler: StorageHandler) -> DoneCommand {
+        DoneCommand {
+            title: String::from("Done"),
+            description: String::from("Marks tasks as finished or reopens them with --undo"),
+            usage: String::from("done [id...] [--undo]"),
+            arguments: Arguments::default(),
+            storage_handler,
+        }
+    }
+
+    /// Parses the raw command line arguments and stores them in the command.
+    /// If IDs are passed, the tasks are updated without any prompt
+    pub fn with_arguments(mut self, raw: Vec<String>) -> DoneCommand {
+        self.arguments = Arguments::parse(raw, &["undo"]);
+        self
+    }
+
+    /// Sets the status of all tasks whose IDs are passed as arguments.
+    /// Nothing is updated if a single ID is invalid or unknown
+    fn update_by_arguments(&mut self, status: Status) -> AppResult<()> {
+        let ids = self.arguments.ids()?;
+        let mut tasks = vec![];
+        for id in ids {
+            match self.storage_handler.get_task_by_id(id)? {
+                Some(task) => tasks.push(Task { status, ..task }),
+                None => {
+                    return Err(AppError::NotFound(format!(
+                        "There is no task with ID {}",
+                        id
+                    )))
+                }
+            }
+        }
+        self.storage_handler.validate_finished(&tasks)?;
+        let all_tasks = self.storage_handler.get_all_tasks()?;
+        for task in tasks.iter() {
+            warn_open_blockers(task, &all_tasks);
+        }
+        let occurrences = self
+            .storage_handler
+            .write_completed_tasks(tasks, due_date::today())?;
+        print_occurrences(&occurrences);
+        Ok(())
+    }
+
+    /// Opens a prompt with all tasks that can be updated
+    /// and updates the selected one
+    fn open_task_prompt(&mut self, status: Status) -> AppResult<()> {
+        let tasks = self
+            .storage_handler
+            .get_all_tasks()?
+            .into_iter()
+            .filter(|task| task.is_closed() != status.is_closed())
+            .collect::<Vec<Task>>();
+        if tasks.is_empty() {
+            println!("No tasks given");
+            return Ok(());
+        }
+        let items = tasks
+            .iter()
+            .map(|task| task.title.clone())
+            .collect::<Vec<String>>();
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .items(&items)
+            .default(0)
+            .interact_on_opt(&Term::stderr());
+        let val = self.handle_select_error(selection)?;
+        let task = Task {
+            status,
+            ..tasks[val].clone()
+        };
+        self.storage_handler
+            .validate_finished(std::slice::from_ref(&task))?;
+        warn_open_blockers(&task, &self.storage_handler.get_all_tasks()?);
+        let occurrences = self
+            .storage_handler
+            .write_completed_tasks(vec![task], due_date::today())?;
+        print_occurrences(&occurrences);
+        Ok(())
+    }
+}
+
+impl Command for DoneCommand {
+    /// This method is called on command execution.
+    /// Contains the main code of the command
+    fn execute(&mut self) -> AppResult<()> {
+        let status = match self.arguments.has_flag("undo") {
+            true => Status::Todo,
+            false => Status::Done,
+        };
+        if self.arguments.positional().is_empty() {
+            return self.open_task_prompt(status);
+        }
+        self.update_by_arguments(status)
+    }
+
+    /// Gets the base command information
+    fn get_command_info(&mut self) -> CommandInfo {
+        CommandInfo {
+            title: self.title.clone(),
+            description: self.description.clone(),
+            usage: self.usage.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage_handler::memory_backend::MemoryBackend;
+
+    #[test]
+    fn updates_all_tasks_passed_as_arguments() {
+        let mut storage_handler = StorageHandler::with_backend(Box::new(MemoryBackend::new()));
+        for title in ["a", "b", "c"] {
+            storage_handler
+                .add_single_task(Task::new(title.to_string(), None))
+                .unwrap();
+        }
+        let mut command = DoneCommand::with_storage(storage_handler)
+            .with_arguments(vec!["0".to_string(), "2".to_string()]);
+        command.execute().unwrap();
+        let statuses = command
+            .storage_handler
+            .get_all_tasks()
+            .unwrap()
+            .iter()
+            .map(|task| task.status)
+            .collect::<Vec<Status>>();
+        assert_eq!(statuses, [Status::Done, Status::Todo, Status::Done]);
+    }
+}
@@ -0,0 +1,471 @@
+use crate::commands::command_trait::{Command, CommandInfo};
+use crate::commands::task_format::{print_occurrences, warn_open_blockers};
+use crate::error::{AppError, AppResult};
+use crate::inputs::arguments::{parse_id_list, Arguments};
+use crate::inputs::due_date;
+use crate::inputs::error_handling::InputErrorHandling;
+use crate::inputs::notes;
+use crate::inputs::recurrence;
+use crate::inputs::tags;
+use crate::storage_handler::recurrence::Recurrence;
+use crate::storage_handler::status::Status;
+use crate::storage_handler::{Priority, StorageHandler, Task};
+use dialoguer::console::{style, Term};
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::{Confirm, Input, Select};
+
+/// All flags that change a field of the task
+const FIELD_FLAGS: [&str; 17] = [
+    "title",
+    "group",
+    "no-group",
+    "due",
+    "no-due",
+    "priority",
+    "status",
+    "tags",
+    "no-tags",
+    "notes",
+    "no-notes",
+    "repeat",
+    "no-repeat",
+    "parent",
+    "no-parent",
+    "depends-on",
+    "no-depends-on",
+];
+
+/// Defines the edit command that changes
+/// the fields of an existing task
+pub struct EditCommand {
+    title: String,
+    description: String,
+    usage: String,
+    arguments: Arguments,
+    storage_handler: StorageHandler,
+}
+
+impl InputErrorHandling for EditCommand {}
+
+impl EditCommand {
+    /// Creates a new instance of the edit command
+    /// and initializes the storage handler in it.
+    pub fn new() -> EditCommand {
+        EditCommand::with_storage(StorageHandler::new())
+    }
+
+    /// Creates a new instance of the edit command
+    /// that uses the given storage handler
+    pub fn with_storage(storage_handler: StorageHandler) -> EditCommand {
+        EditCommand {
+            title: String::from("Edit"),
+            description: String::from("Changes the fields of a task. The ID of the task is kept"),
+            usage: String::from(
+                "edit [id] [--title <title>] [--group <group>|--no-group] [--due <date>|--no-due] [--priority <priority>] [--status <status>] [--tags <+tag ...>|--no-tags] [--notes <text|->|--no-notes] [--repeat <rule>|--no-repeat] [--parent <id>|--no-parent] [--depends-on <id,...>|--no-depends-on] [--yes]",
+            ),
+            arguments: Arguments::default(),
+            storage_handler,
+        }
+    }
+
+    /// Parses the raw command line arguments and stores them in the command.
+    /// If field flags are passed, the task is changed without any field prompt
+    pub fn with_arguments(mut self, raw: Vec<String>) -> EditCommand {
+        self.arguments = Arguments::parse(
+            raw,
+            &[
+                "no-group",
+                "no-due",
+                "no-tags",
+                "no-notes",
+                "no-repeat",
+                "no-parent",
+                "no-depends-on",
+                "yes",
+            ],
+        );
+        self
+    }
+
+    /// Gets the task by the ID passed as argument or
+    /// opens a prompt for selecting the task
+    fn get_task(&mut self) -> AppResult<Task> {
+        if let Some(id) = self.arguments.ids()?.first() {
+            return self
+                .storage_handler
+                .get_task_by_id(*id)?
+                .ok_or_else(|| AppError::NotFound(format!("There is no task with ID {}", id)));
+        }
+        let tasks = self.storage_handler.get_all_tasks()?;
+        if tasks.is_empty() {
+            return Err(AppError::NotFound("No tasks given".to_string()));
+        }
+        let items = tasks
+            .iter()
+            .map(|task| task.title.clone())
+            .collect::<Vec<String>>();
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .items(&items)
+            .default(0)
+            .interact_on_opt(&Term::stderr());
+        let val = self.handle_select_error(selection)?;
+        Ok(tasks[val].clone())
+    }
+
+    /// Checks if any flag that changes a field has been passed
+    fn has_field_arguments(&self) -> bool {
+        FIELD_FLAGS.iter().any(|flag| self.arguments.has_flag(flag))
+    }
+
+    /// Parses the tags of the input. Tags are entered as +tag
+    /// and separated by spaces or commas
+    fn parse_tags(&self, input: &str) -> AppResult<Vec<String>> {
+        let (rest, tags) = tags::extract_tags(&input.replace(',', " "));
+        if !rest.is_empty() {
+            return Err(AppError::Validation(format!(
+                "{} is not a valid tag. Use +tag",
+                rest
+            )));
+        }
+        Ok(tags)
+    }
+
+    /// Sets the title of the task. Words starting with a + are
+    /// removed from the title and added as tags to the task
+    fn set_title(&self, task: &mut Task, input: &str) {
+        let (title, title_tags) = tags::extract_tags(input);
+        task.title = title;
+        for tag in title_tags {
+            if !task.tags.contains(&tag) {
+                task.tags.push(tag);
+            }
+        }
+    }
+
+    /// Changes all fields of the task that are passed as flags
+    fn apply_arguments(&mut self, task: &mut Task) -> AppResult<()> {
+        if self.arguments.has_flag("no-group") {
+            task.group = None;
+        } else if let Some(group) = self.arguments.flag_value("group") {
+            task.group = Some(group);
+        }
+        if self.arguments.has_flag("no-due") {
+            task.due = None;
+        } else if let Some(value) = self.arguments.flag_value("due") {
+            task.due = Some(due_date::parse_due_date(&value, due_date::today())?);
+        }
+        if let Some(value) = self.arguments.flag_value("priority") {
+            task.priority = Priority::parse(&value)?;
+        }
+        if let Some(value) = self.arguments.flag_value("status") {
+            task.status = Status::parse(&value)?;
+        }
+        if self.arguments.has_flag("no-tags") {
+            task.tags = vec![];
+        } else if let Some(value) = self.arguments.flag_value("tags") {
+            task.tags = self.parse_tags(&value)?;
+        }
+        if let Some(title) = self.arguments.flag_value("title") {
+            self.set_title(task, &title);
+        }
+        if self.arguments.has_flag("no-notes") {
+            task.notes = None;
+        } else if let Some(value) = self.arguments.flag_value("notes") {
+            task.notes = notes::read_notes_argument(&value)?;
+        }
+        if self.arguments.has_flag("no-repeat") {
+            task.recurrence = None;
+        } else if let Some(value) = self.arguments.flag_value("repeat") {
+            task.recurrence = Some(Recurrence::parse(&value)?);
+        }
+        if self.arguments.has_flag("no-parent") {
+            task.parent = None;
+        } else if let Some(value) = self.arguments.flag_value("parent") {
+            let id = value
+                .parse::<u64>()
+                .map_err(|_| AppError::Validation(format!("{} is not a valid task ID", value)))?;
+            task.parent = Some(id);
+        }
+        if self.arguments.has_flag("no-depends-on") {
+            task.depends_on = vec![];
+        } else if self.arguments.has_flag("depends-on") {
+            task.depends_on = self.arguments.id_list("depends-on")?;
+        }
+        Ok(())
+    }
+
+    /// Gets the name and the displayed value of all fields of the task
+    fn describe_task(&self, task: &Task) -> Vec<(&'static str, String)> {
+        let none = || "none".to_string();
+        vec![
+            ("Title", task.title.clone()),
+            ("Group", task.group.clone().unwrap_or_else(none)),
+            (
+                "Due date",
+                task.due.map(|due| due.to_string()).unwrap_or_else(none),
+            ),
+            ("Priority", task.priority.name().to_string()),
+            ("Status", task.status.name().to_string()),
+            (
+                "Tags",
+                match task.tags.is_empty() {
+                    true => none(),
+                    false => task
+                        .tags
+                        .iter()
+                        .map(|tag| format!("+{}", tag))
+                        .collect::<Vec<String>>()
+                        .join(" "),
+                },
+            ),
+            (
+                "Notes",
+                task.notes
+                    .as_ref()
+                    .map(|notes| notes.replace('\n', " ↵ "))
+                    .unwrap_or_else(none),
+            ),
+            (
+                "Repeat",
+                task.recurrence
+                    .as_ref()
+                    .map(|recurrence| recurrence.to_string())
+                    .unwrap_or_else(none),
+            ),
+            (
+                "Parent",
+                task.parent
+                    .map(|parent| parent.to_string())
+                    .unwrap_or_else(none),
+            ),
+            (
+                "Dependencies",
+                match task.depends_on.is_empty() {
+                    true => none(),
+                    false => task
+                        .depends_on
+                        .iter()
+                        .map(|id| id.to_string())
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                },
+            ),
+        ]
+    }
+
+    /// Opens a prompt for selecting one of the available values.
+    /// The current value is selected by default
+    fn select_value(&mut self, prompt: &str, items: &[String], current: usize) -> AppResult<usize> {
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(prompt)
+            .items(items)
+            .default(current)
+            .interact_on_opt(&Term::stderr());
+        self.handle_select_error(selection)
+    }
+
+    /// Opens the prompt for changing a single field of the task
+    fn edit_field(&mut self, task: &mut Task, field: &str) -> AppResult<()> {
+        match field {
+            "Title" => {
+                let input: String = Input::new()
+                    .with_prompt("Title")
+                    .with_initial_text(task.title.clone())
+                    .interact_text()?;
+                self.set_title(task, &input);
+            }
+            "Group" => {
+                let mut items = vec!["❌ no group".to_string()];
+                items.extend(self.storage_handler.get_all_groups()?);
+                let current = items
+                    .iter()
+                    .position(|group| task.group.as_ref() == Some(group))
+                    .unwrap_or(0);
+                task.group = match self.select_value("Group", &items, current)? {
+                    0 => None,
+                    val => Some(items[val].clone()),
+                };
+            }
+            "Due date" => task.due = due_date::prompt_due_date(task.due)?,
+            "Priority" => {
+                let items = Priority::ALL
+                    .iter()
+                    .map(|priority| format!("{} {}", priority.name(), priority.marker()))
+                    .collect::<Vec<String>>();
+                let current = Priority::ALL
+                    .iter()
+                    .position(|priority| *priority == task.priority)
+                    .unwrap_or(0);
+                task.priority = Priority::ALL[self.select_value("Priority", &items, current)?];
+            }
+            "Status" => {
+                let items = Status::ALL
+                    .iter()
+                    .map(|status| format!("{} {}", status.symbol(), status.name()))
+                    .collect::<Vec<String>>();
+                let current = Status::ALL
+                    .iter()
+                    .position(|status| *status == task.status)
+                    .unwrap_or(0);
+                task.status = Status::ALL[self.select_value("Status", &items, current)?];
+            }
+            "Tags" => {
+                let current = task
+                    .tags
+                    .iter()
+                    .map(|tag| format!("+{}", tag))
+                    .collect::<Vec<String>>();
+                let input: String = Input::new()
+                    .with_prompt("Tags (e.g. +backend +ci; empty for none)")
+                    .with_initial_text(current.join(" "))
+                    .allow_empty(true)
+                    .interact_text()?;
+                task.tags = self.parse_tags(&input)?;
+            }
+            "Notes" => task.notes = notes::prompt_notes(task.notes.as_deref())?,
+            "Repeat" => task.recurrence = recurrence::prompt_recurrence(task.recurrence.as_ref())?,
+            "Parent" => {
+                let candidates = self
+                    .storage_handler
+                    .get_tasks_by_group(task.group.as_deref())?
+                    .into_iter()
+                    .filter(|other| other.id != task.id)
+                    .collect::<Vec<Task>>();
+                let mut items = vec!["❌ no parent".to_string()];
+                items.extend(candidates.iter().map(|other| other.title.clone()));
+                let current = candidates
+                    .iter()
+                    .position(|other| Some(other.id) == task.parent)
+                    .map(|index| index + 1)
+                    .unwrap_or(0);
+                task.parent = match self.select_value("Parent task", &items, current)? {
+                    0 => None,
+                    val => Some(candidates[val - 1].id),
+                };
+            }
+            "Dependencies" => {
+                let current = task
+                    .depends_on
+                    .iter()
+                    .map(|id| id.to_string())
+                    .collect::<Vec<String>>();
+                let input: String = Input::new()
+                    .with_prompt("Depends on (e.g. 3,4; empty for none)")
+                    .with_initial_text(current.join(","))
+                    .allow_empty(true)
+                    .interact_text()?;
+                task.depends_on = parse_id_list(&input)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Opens a prompt with all fields of the task until editing is finished.
+    /// Invalid input is reported and the field can be changed again
+    fn open_field_prompt(&mut self, task: &mut Task) -> AppResult<()> {
+        loop {
+            let fields = self.describe_task(task);
+            let mut items = fields
+                .iter()
+                .map(|(name, value)| format!("{}: {}", name, value))
+                .collect::<Vec<String>>();
+            items.push("✔ done".to_string());
+            let selection = Select::with_theme(&ColorfulTheme::default())
+                .with_prompt("Field")
+                .items(&items)
+                .default(0)
+                .interact_on_opt(&Term::stderr());
+            let val = self.handle_select_error(selection)?;
+            if val == fields.len() {
+                return Ok(());
+            }
+            match self.edit_field(task, fields[val].0) {
+                Err(AppError::Validation(message)) => eprintln!("ERROR: {}", message),
+                result => result?,
+            }
+        }
+    }
+
+    /// Checks if the changed task can be saved
+    fn validate_task(&mut self, task: &Task) -> AppResult<()> {
+        if task.title.is_empty() {
+            return Err(AppError::Validation(
+                "The title must not be empty".to_string(),
+            ));
+        }
+        if let Some(group) = &task.group {
+            if !self.storage_handler.get_all_groups()?.contains(group) {
+                return Err(AppError::NotFound(format!(
+                    "The group {} does not exist",
+                    group
+                )));
+            }
+        }
+        self.storage_handler.validate_parent(task)?;
+        self.storage_handler.validate_dependencies(task)?;
+        self.storage_handler
+            .validate_finished(std::slice::from_ref(task))
+    }
+
+    /// Prints all fields that differ between the stored and the changed
+    /// task. Returns false if nothing has been changed
+    fn print_diff(&self, original: &Task, task: &Task) -> bool {
+        let mut changed = false;
+        for ((name, old), (_, new)) in self
+            .describe_task(original)
+            .into_iter()
+            .zip(self.describe_task(task))
+        {
+            if old != new {
+                eprintln!("{}", style(format!("- {}: {}", name, old)).red());
+                eprintln!("{}", style(format!("+ {}: {}", name, new)).green());
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+impl Command for EditCommand {
+    /// This method is called on command execution.
+    /// Contains the main code of the command
+    fn execute(&mut self) -> AppResult<()> {
+        let original = self.get_task()?;
+        let mut task = original.clone();
+        match self.has_field_arguments() {
+            true => self.apply_arguments(&mut task)?,
+            false => self.open_field_prompt(&mut task)?,
+        }
+        self.validate_task(&task)?;
+        if !self.print_diff(&original, &task) {
+            println!("No changes");
+            return Ok(());
+        }
+        let status_changed = task.status != original.status;
+        if status_changed {
+            warn_open_blockers(&task, &self.storage_handler.get_all_tasks()?);
+        }
+        if !self.arguments.has_flag("yes") && Term::stderr().is_term() {
+            let confirmation = Confirm::new().with_prompt("Save?").interact();
+            self.handle_confirm_error(confirmation)?;
+        }
+        if !status_changed {
+            return self.storage_handler.write_task_data(vec![task]);
+        }
+        let occurrences = self
+            .storage_handler
+            .write_completed_tasks(vec![task], due_date::today())?;
+        print_occurrences(&occurrences);
+        Ok(())
+    }
+
+    /// Gets the base command information
+    fn get_command_info(&mut self) -> CommandInfo {
+        CommandInfo {
+            title: self.title.clone(),
+            description: self.description.clone(),
+            usage: self.usage.clone(),
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use crate::commands::command_trait::{Command, CommandInfo};
+use crate::error::{AppError, AppResult};
+use crate::inputs::arguments::Arguments;
+use crate::inputs::error_handling::InputErrorHandling;
+use crate::storage_handler::StorageHandler;
+use dialoguer::console::Term;
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::{Confirm, Input, Select};
+
+/// Defines what happens with the tasks of a deleted group
+enum GroupDeleteAction {
+    Cascade,
+    Ungroup,
+    Move(String),
+}
+
+/// Defines the group command that manages
+/// the existing task groups
+pub struct GroupCommand {
+    title: String,
+    description: String,
+    usage: String,
+    arguments: Arguments,
+    storage_handler: StorageHandler,
+}
+
+impl InputErrorHandling for GroupCommand {}
+
+impl GroupCommand {
+    /// Creates a new instance of the group command
+    /// and initializes the storage handler in it.
+    pub fn new() -> GroupCommand {
+        GroupCommand::with_storage(StorageHandler::new())
+    }
+
+    /// Creates a new instance of the group command
+    /// that uses the given storage handler
+    pub fn with_storage(storage_handler: StorageHandler) -> GroupCommand {
+        GroupCommand {
+            title: String::from("Group"),
+            description: String::from("Lists, renames, deletes and merges task groups"),
+            usage: String::from(
+                "group list | group rename <old> <new> | group delete <name> [--cascade|--ungroup|--move-to <group>] | group merge <source> <target> | or just type the command",
+            ),
+            arguments: Arguments::default(),
+            storage_handler,
+        }
+    }
+
+    /// Parses the raw command line arguments and stores them in the command.
+    /// If an action is passed, the groups are changed without any prompt
+    pub fn with_arguments(mut self, raw: Vec<String>) -> GroupCommand {
+        self.arguments = Arguments::parse(raw, &["cascade", "ungroup"]);
+        self
+    }
+
+    /// Gets the positional argument at the given index.
+    /// If it is missing, the usage is returned as error
+    fn get_positional(&self, index: usize) -> AppResult<String> {
+        self.arguments
+            .positional()
+            .get(index)
+            .cloned()
+            .ok_or_else(|| AppError::Validation(format!("Usage: {}", self.usage)))
+    }
+
+    /// Prints all groups with the number of their tasks
+    fn print_groups(&mut self) -> AppResult<()> {
+        let groups = self.storage_handler.get_all_groups()?;
+        if groups.is_empty() {
+            println!("No groups given");
+        }
+        for group in groups {
+            let count = self.storage_handler.get_tasks_by_group(Some(&group))?.len();
+            println!("{:>4}  {}", count, group);
+        }
+        Ok(())
+    }
+
+    /// Opens a prompt for selecting one of the groups.
+    /// The given group is not offered
+    fn select_group(&mut self, prompt: &str, except: Option<&str>) -> AppResult<String> {
+        let groups = self
+            .storage_handler
+            .get_all_groups()?
+            .into_iter()
+            .filter(|group| Some(group.as_str()) != except)
+            .collect::<Vec<String>>();
+        if groups.is_empty() {
+            return Err(AppError::NotFound("No groups given".to_string()));
+        }
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(prompt)
+            .items(&groups)
+            .default(0)
+            .interact_on_opt(&Term::stderr());
+        let val = self.handle_select_error(selection)?;
+        Ok(groups[val].clone())
+    }
+
+    /// Opens a prompt for confirming the change.
+    /// Declining the prompt cancels the command
+    fn confirm_selection(&mut self) -> AppResult<()> {
+        let confirmation = Confirm::new().with_prompt("Save?").interact();
+        self.handle_confirm_error(confirmation)
+    }
+
+    /// Moves all tasks of the source group into the
+    /// target group and removes the source group
+    fn merge_groups(&mut self, source: &str, target: &str) -> AppResult<()> {
+        self.storage_handler.validate_group_exists(source)?;
+        self.storage_handler.validate_group_exists(target)?;
+        if source == target {
+            return Err(AppError::Validation(
+                "A group cannot be merged into itself".to_string(),
+            ));
+        }
+        self.storage_handler
+            .move_group_tasks(source, Some(target))?;
+        self.storage_handler.delete_group(source)
+    }
+
+    /// Removes the group. Its tasks are deleted, ungrouped or
+    /// moved into another group depending on the action
+    fn delete_group(&mut self, name: &str, action: GroupDeleteAction) -> AppResult<()> {
+        match action {
+            GroupDeleteAction::Cascade => {
+                let ids = self
+                    .storage_handler
+                    .get_tasks_by_group(Some(name))?
+                    .iter()
+                    .map(|task| task.id)
+                    .collect::<Vec<u64>>();
+                self.storage_handler.delete_tasks(&ids)?;
+            }
+            GroupDeleteAction::Ungroup => self.storage_handler.move_group_tasks(name, None)?,
+            GroupDeleteAction::Move(target) => {
+                self.storage_handler.validate_group_exists(&target)?;
+                if target == name {
+                    return Err(AppError::Validation(
+                        "The tasks cannot be moved into the deleted group".to_string(),
+                    ));
+                }
+                self.storage_handler.move_group_tasks(name, Some(&target))?;
+            }
+        }
+        self.storage_handler.delete_group(name)
+    }
+
+    /// Gets the action for the tasks of the deleted group from the arguments
+    fn get_delete_action_argument(&mut self) -> Option<GroupDeleteAction> {
+        if self.arguments.has_flag("cascade") {
+            return Some(GroupDeleteAction::Cascade);
+        }
+        if self.arguments.has_flag("ungroup") {
+            return Some(GroupDeleteAction::Ungroup);
+        }
+        self.arguments
+            .flag_value("move-to")
+            .map(GroupDeleteAction::Move)
+    }
+
+    /// Opens a prompt for selecting what happens with
+    /// the tasks of the group that is deleted
+    fn open_delete_action_prompt(&mut self, name: &str) -> AppResult<GroupDeleteAction> {
+        let items = [
+            "Delete the tasks too",
+            "Keep the tasks without a group",
+            "Move the tasks into another group",
+        ];
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("The group has tasks")
+            .items(&items)
+            .default(1)
+            .interact_on_opt(&Term::stderr());
+        match self.handle_select_error(selection)? {
+            0 => Ok(GroupDeleteAction::Cascade),
+            1 => Ok(GroupDeleteAction::Ungroup),
+            _ => Ok(GroupDeleteAction::Move(
+                self.select_group("Move the tasks into", Some(name))?,
+            )),
+        }
+    }
+
+    /// Opens the prompts for deleting the group. If the group has tasks,
+    /// the user selects what happens with them
+    pub fn open_delete_prompt(&mut self, name: &str) -> AppResult<()> {
+        let action = match self
+            .storage_handler
+            .get_tasks_by_group(Some(name))?
+            .is_empty()
+        {
+            true => GroupDeleteAction::Ungroup,
+            false => self.open_delete_action_prompt(name)?,
+        };
+        self.confirm_selection()?;
+        self.delete_group(name, action)
+    }
+
+    /// Deletes the group passed as argument. If the group has tasks
+    /// and no action is passed, the action is selected in a prompt
+    fn delete_by_arguments(&mut self) -> AppResult<()> {
+        let name = self.get_positional(1)?;
+        self.storage_handler.validate_group_exists(&name)?;
+        if let Some(action) = self.get_delete_action_argument() {
+            return self.delete_group(&name, action);
+        }
+        if self
+            .storage_handler
+            .get_tasks_by_group(Some(&name))?
+            .is_empty()
+        {
+            return self.delete_group(&name, GroupDeleteAction::Ungroup);
+        }
+        if !Term::stderr().is_term() {
+            return Err(AppError::Validation(
+                "The group has tasks. Pass --cascade, --ungroup or --move-to <group>".to_string(),
+            ));
+        }
+        let action = self.open_delete_action_prompt(&name)?;
+        self.delete_group(&name, action)
+    }
+
+    /// Opens the prompts for selecting a group
+    /// and the action that is done with it
+    fn open_group_prompt(&mut self) -> AppResult<()> {
+        let name = self.select_group("Group", None)?;
+        let items = ["Rename", "Delete", "Merge into another group"];
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(&name)
+            .items(&items)
+            .default(0)
+            .interact_on_opt(&Term::stderr());
+        match self.handle_select_error(selection)? {
+            0 => {
+                let new_name: String = Input::new()
+                    .with_prompt("New name")
+                    .with_initial_text(name.clone())
+                    .interact_text()?;
+                self.confirm_selection()?;
+                self.storage_handler.rename_group(&name, new_name.trim())
+            }
+            1 => self.open_delete_prompt(&name),
+            _ => {
+                let target = self.select_group("Merge into", Some(&name))?;
+                self.confirm_selection()?;
+                self.merge_groups(&name, &target)
+            }
+        }
+    }
+}
+
+impl Command for GroupCommand {
+    /// This method is called on command execution.
+    /// Contains the main code of the command
+    fn execute(&mut self) -> AppResult<()> {
+        let action = match self.arguments.positional().first() {
+            Some(action) => action.clone(),
+            None => return self.open_group_prompt(),
+        };
+        match action.as_str() {
+            "list" => self.print_groups(),
+            "rename" => {
+                let (old, new) = (self.get_positional(1)?, self.get_positional(2)?);
+                self.storage_handler.rename_group(&old, new.trim())
+            }
+            "delete" => self.delete_by_arguments(),
+            "merge" => {
+                let (source, target) = (self.get_positional(1)?, self.get_positional(2)?);
+                self.merge_groups(&source, &target)
+            }
+            _ => Err(AppError::Validation(format!(
+                "{} is not a valid group action",
+                action
+            ))),
+        }
+    }
+
+    /// Gets the base command information
+    fn get_command_info(&mut self) -> CommandInfo {
+        CommandInfo {
+            title: self.title.clone(),
+            description: self.description.clone(),
+            usage: self.usage.clone(),
+        }
+    }
+}
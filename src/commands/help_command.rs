@@ -1,9 +1,20 @@
 use crate::commands::add_command::AddCommand;
+use crate::commands::board_command::BoardCommand;
 use crate::commands::command_trait::{Command, CommandInfo};
 use crate::commands::delete_command::DeleteCommand;
+use crate::commands::depend_command::DependCommand;
+use crate::commands::doctor_command::DoctorCommand;
+use crate::commands::done_command::DoneCommand;
+use crate::commands::edit_command::EditCommand;
+use crate::commands::group_command::GroupCommand;
 use crate::commands::list_command::ListCommand;
-use crate::handler::commands::Commands;
-use crate::handler::commands::Commands::Help;
+use crate::commands::move_command::MoveCommand;
+use crate::commands::next_command::NextCommand;
+use crate::commands::search_command::SearchCommand;
+use crate::commands::show_command::ShowCommand;
+use crate::commands::tags_command::TagsCommand;
+use crate::commands::tui_command::TuiCommand;
+use crate::error::AppResult;
 
 /// Defines the base help command
 pub struct HelpCommand {
@@ -27,12 +38,24 @@ impl HelpCommand {
 impl Command for HelpCommand {
     /// This method is called on command execute.
     /// Contains the main code of the command
-    fn execute(&mut self) {
+    fn execute(&mut self) -> AppResult<()> {
         let commands = vec![
             HelpCommand::new().get_command_info(),
             ListCommand::new().get_command_info(),
             AddCommand::new().get_command_info(),
-            DeleteCommand::new().get_command_info()
+            DeleteCommand::new().get_command_info(),
+            DoneCommand::new().get_command_info(),
+            DoctorCommand::new().get_command_info(),
+            TagsCommand::new().get_command_info(),
+            ShowCommand::new().get_command_info(),
+            NextCommand::new().get_command_info(),
+            DependCommand::new().get_command_info(),
+            EditCommand::new().get_command_info(),
+            GroupCommand::new().get_command_info(),
+            MoveCommand::new().get_command_info(),
+            TuiCommand::new().get_command_info(),
+            BoardCommand::new().get_command_info(),
+            SearchCommand::new().get_command_info(),
         ];
         for command in commands {
             println!("TITLE: {}", command.title);
@@ -40,6 +63,7 @@ impl Command for HelpCommand {
             println!("USAGE: {}", command.usage);
             print!("\n\n");
         }
+        Ok(())
     }
 
     /// Returns the base information about the command
@@ -1,17 +1,31 @@
 use crate::commands::command_trait::{Command, CommandInfo};
+use crate::commands::move_command::MoveCommand;
+use crate::commands::task_format::{format_task, print_occurrences, warn_open_blockers};
+use crate::commands::task_search::{contains_text, fuzzy_select, SelectItem};
+use crate::commands::task_tree::{build_tree, TreeEntry};
+use crate::error::{AppError, AppResult};
+use crate::inputs::arguments::Arguments;
+use crate::inputs::due_date;
 use crate::inputs::error_handling::InputErrorHandling;
+use crate::inputs::tags::TagFilter;
+use crate::storage_handler::status::{Status, Workflow};
 use crate::storage_handler::{StorageHandler, Task};
+use chrono::{Local, NaiveDate};
 use dialoguer::console::Term;
 use dialoguer::theme::ColorfulTheme;
 use dialoguer::{Confirm, MultiSelect, Select};
-use std::error::Error;
+use std::cmp::Reverse;
+use std::collections::HashSet;
 
 /// Defines the list command
 pub struct ListCommand {
     title: String,
     description: String,
     usage: String,
+    arguments: Arguments,
     storage_handler: StorageHandler,
+    collapsed: HashSet<u64>,
+    all_tasks: Vec<Task>,
 }
 
 impl InputErrorHandling for ListCommand {}
@@ -21,59 +35,297 @@ impl ListCommand {
     /// the required base data and a new instance of the
     /// storage handler
     pub fn new() -> ListCommand {
+        ListCommand::with_storage(StorageHandler::new())
+    }
+
+    /// Creates a new instance of the list command
+    /// that uses the given storage handler
+    pub fn with_storage(storage_handler: StorageHandler) -> ListCommand {
         ListCommand {
             title: String::from("List"),
             description: String::from("Lists all tasks that are not done yet"),
-            usage: String::from("Just type list"),
-            storage_handler: StorageHandler::new(),
+            usage: String::from(
+                "list [+tag...] [-tag...] [--group <group>] [--search <text>] [--completed-since <7d|date>] [--sort priority|due|updated] [--plain|--toggle]",
+            ),
+            arguments: Arguments::default(),
+            storage_handler,
+            collapsed: HashSet::new(),
+            all_tasks: vec![],
+        }
+    }
+
+    /// Parses the raw command line arguments and stores them in the command.
+    /// If --plain or --group is passed, the tasks are printed instead of prompted.
+    /// With --toggle, the tasks are checked and unchecked in a single prompt
+    pub fn with_arguments(mut self, raw: Vec<String>) -> ListCommand {
+        self.arguments = Arguments::parse(raw, &["plain", "toggle"]);
+        self
+    }
+
+    /// Gets all tasks that match the arguments.
+    /// With --group, only the tasks of the group are returned
+    fn get_tasks_by_arguments(&mut self) -> AppResult<Vec<Task>> {
+        self.all_tasks = self.storage_handler.get_all_tasks()?;
+        let tasks = match self.arguments.flag_value("group") {
+            Some(group) => {
+                if !self.storage_handler.get_all_groups()?.contains(&group) {
+                    return Err(AppError::NotFound(format!(
+                        "The group {} does not exist",
+                        group
+                    )));
+                }
+                self.storage_handler.get_tasks_by_group(Some(&group))?
+            }
+            None => self.all_tasks.clone(),
+        };
+        self.filter_and_sort_tasks(tasks)
+    }
+
+    /// Prints all tasks as plain text with their IDs. This is used
+    /// if arguments are passed or the terminal is not interactive
+    fn print_tasks(&mut self) -> AppResult<()> {
+        let tasks = self.get_tasks_by_arguments()?;
+        for entry in build_tree(tasks, &HashSet::new()).iter() {
+            let task = &entry.task;
+            let mut line = format!("{:>4}  {}", task.id, self.format_entry(entry));
+            if let Some(name) = &task.group {
+                line = line + " (" + name + ")";
+            }
+            println!("{}", line);
         }
+        Ok(())
     }
 
-    /// Gets the related symbol based on if the task is
-    /// finished or not. The returned strings are symbols
-    /// and may not be supported by every terminal / cmd
-    fn get_checked_symbol(&mut self, task: &Task) -> String {
-        if task.finished {
-            return "✅".to_string();
+    /// Checks if the tasks should be printed as plain text
+    /// instead of opening the interactive prompts
+    fn is_print_mode(&mut self) -> bool {
+        self.arguments.has_flag("plain")
+            || self.arguments.has_flag("group")
+            || !Term::stderr().is_term()
+    }
+
+    /// Checks if the task has been completed in the time range
+    /// passed with --completed-since. All tasks match without the flag
+    fn matches_completed_since(&self, task: &Task, since: Option<NaiveDate>) -> bool {
+        match since {
+            Some(since) => task
+                .completed_at
+                .is_some_and(|completed| completed.with_timezone(&Local).date_naive() >= since),
+            None => true,
         }
-        return "❌".to_string();
     }
 
-    /// Opens a submenu with all tasks of a specific group
-    /// that can be selected and updated
-    fn open_group_prompt(&mut self, title: String) {
-        let mut single_tasks = self
+    /// Removes all tasks that do not match the tag filter or search and sorts the
+    /// remaining ones by their priority, starting with the highest one.
+    /// With --sort due, the tasks are sorted by their due date first. With --sort updated,
+    /// the tasks that have not been updated for the longest time come first.
+    /// Tasks that are equal keep their order
+    fn filter_and_sort_tasks(&mut self, mut tasks: Vec<Task>) -> AppResult<Vec<Task>> {
+        let filter = TagFilter::parse(self.arguments.positional())?;
+        let since = match self.arguments.flag_value("completed-since") {
+            Some(value) => Some(due_date::parse_since(&value, due_date::today())?),
+            None => None,
+        };
+        let search = self.arguments.flag_value("search");
+        tasks.retain(|task| {
+            filter.matches(task)
+                && search.as_ref().is_none_or(|text| contains_text(task, text))
+                && self.matches_completed_since(task, since)
+        });
+        tasks.sort_by_key(|task| Reverse(task.priority));
+        match self.arguments.flag_value("sort").as_deref() {
+            None | Some("priority") => {}
+            Some("due") => tasks.sort_by_key(|task| (task.due.is_none(), task.due)),
+            Some("updated") => tasks.sort_by_key(|task| task.updated_at),
+            Some(value) => {
+                return Err(AppError::Validation(format!(
+                    "Cannot sort tasks by {}",
+                    value
+                )));
+            }
+        }
+        Ok(tasks)
+    }
+
+    /// Formats the task as part of the tree. Subtasks are indented
+    /// and tasks with subtasks show how many of them are closed
+    fn format_entry(&mut self, entry: &TreeEntry) -> String {
+        let mut line = "  ".repeat(entry.depth);
+        if entry.children > 0 {
+            match self.collapsed.contains(&entry.task.id) {
+                true => line += "▸ ",
+                false => line += "▾ ",
+            }
+        }
+        line += &format_task(&entry.task, &self.all_tasks);
+        if entry.children > 0 {
+            line += &format!("  ({}/{})", entry.closed_children, entry.children);
+        }
+        line
+    }
+
+    /// Opens a prompt for confirming the change and saves the task with
+    /// the new status. Tasks with open subtasks cannot be done, open
+    /// blockers are warned about. Completing a recurring task adds its next occurrence
+    fn update_status(&mut self, task: &Task, status: Status) -> AppResult<()> {
+        let actual = Task {
+            status,
+            ..task.clone()
+        };
+        self.storage_handler
+            .validate_finished(std::slice::from_ref(&actual))?;
+        warn_open_blockers(&actual, &self.all_tasks);
+        let confirmation = Confirm::new().with_prompt("Save?").interact();
+        self.handle_confirm_error(confirmation)?;
+        let occurrences = self
             .storage_handler
-            .get_all_tasks()
+            .write_completed_tasks(vec![actual], due_date::today())?;
+        print_occurrences(&occurrences);
+        Ok(())
+    }
+
+    /// Opens a prompt for checking and unchecking any number of the tasks. Checked
+    /// tasks are done, unchecked ones are reopened. All changes are validated
+    /// together and saved with a single write after one confirmation
+    fn open_toggle_prompt(&mut self, entries: Vec<TreeEntry>) -> AppResult<()> {
+        if entries.is_empty() {
+            println!("No tasks given");
+            return Ok(());
+        }
+        let items = entries
+            .iter()
+            .map(|entry| {
+                let line = "  ".repeat(entry.depth) + &entry.task.title;
+                let line = match &entry.task.group {
+                    Some(group) => line + " (" + group + ")",
+                    None => line,
+                };
+                (line, entry.task.status == Status::Done)
+            })
+            .collect::<Vec<(String, bool)>>();
+        let checked = items
+            .iter()
+            .map(|(_, checked)| *checked)
+            .collect::<Vec<bool>>();
+        let selection = MultiSelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Done tasks (space to toggle)")
+            .items_checked(&items)
+            .interact_on_opt(&Term::stderr())?;
+        let selected = selection.ok_or(AppError::Cancelled)?;
+        let changed = entries
             .into_iter()
-            .filter(|task| task.group == Some(title.clone()))
+            .enumerate()
+            .filter(|(index, _)| selected.contains(index) != checked[*index])
+            .map(|(index, entry)| {
+                let status = match checked[index] {
+                    true => Status::Todo,
+                    false => Status::Done,
+                };
+                Task {
+                    status,
+                    ..entry.task
+                }
+            })
             .collect::<Vec<Task>>();
+        if changed.is_empty() {
+            println!("Nothing changed");
+            return Ok(());
+        }
+        self.storage_handler.validate_finished(&changed)?;
+        for task in changed.iter().filter(|task| task.status == Status::Done) {
+            warn_open_blockers(task, &self.all_tasks);
+        }
+        let confirmation = Confirm::new().with_prompt("Save?").interact();
+        self.handle_confirm_error(confirmation)?;
+        let count = changed.len();
+        let occurrences = self
+            .storage_handler
+            .write_completed_tasks(changed, due_date::today())?;
+        println!("Updated {} tasks", count);
+        print_occurrences(&occurrences);
+        Ok(())
+    }
 
-        let mut items = (&single_tasks)
-            .into_iter()
-            .map(|x| self.get_checked_symbol(&x) + "  " + &x.title)
+    /// Opens a prompt for selecting any status for the task
+    fn open_status_prompt(&mut self, task: &Task) -> AppResult<()> {
+        let items = Status::ALL
+            .iter()
+            .map(|status| format!("{} {}", status.symbol(), status.name()))
             .collect::<Vec<String>>();
-        items.push("← back".to_string());
-        items = items.into_iter().rev().collect();
         let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Status")
+            .items(&items)
+            .default(
+                Status::ALL
+                    .iter()
+                    .position(|status| *status == task.status)
+                    .unwrap_or(0),
+            )
+            .interact_on_opt(&Term::stderr());
+        let val = self.handle_select_error(selection)?;
+        self.update_status(task, Status::ALL[val])
+    }
+
+    /// Opens a prompt with the actions for a task. The status can be moved to
+    /// the next one of the workflow or set directly. The subtasks of tasks
+    /// in a group can be collapsed and expanded
+    fn open_task_prompt(&mut self, group: Option<String>, entry: &TreeEntry) -> AppResult<()> {
+        let task = &entry.task;
+        let next = Workflow::from_env()?.next(task.status);
+        let mut items = vec![
+            format!("Move to {} {}", next.symbol(), next.name()),
+            "Set status".to_string(),
+        ];
+        if group.is_some() && entry.children > 0 {
+            match self.collapsed.contains(&task.id) {
+                true => items.push("Expand subtasks".to_string()),
+                false => items.push("Collapse subtasks".to_string()),
+            }
+        }
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(&task.title)
             .items(&items)
             .default(0)
             .interact_on_opt(&Term::stderr());
-        match self.handle_select_error(selection) {
-            None => {},
-            Some(val) => {
-                if val == 0 {
-                    return self.execute();
-                }
-                if !Confirm::new().with_prompt("Save?").interact().unwrap() {
-                    return;
+        match (self.handle_select_error(selection)?, group) {
+            (0, _) => self.update_status(task, next),
+            (1, _) => self.open_status_prompt(task),
+            (_, Some(group)) => {
+                if !self.collapsed.remove(&task.id) {
+                    self.collapsed.insert(task.id);
                 }
-                single_tasks = single_tasks.into_iter().rev().collect();
-                let mut actual = (single_tasks[val-1]).clone();
-                actual.finished = !actual.finished;
-                single_tasks[val-1] = actual.clone();
-                self.storage_handler.write_task_data(single_tasks);
+                self.open_group_prompt(group)
             }
+            (_, None) => Ok(()),
+        }
+    }
+
+    /// Opens a submenu with all tasks of a specific group
+    /// that can be selected and updated. Subtasks are shown
+    /// as a tree that can be collapsed. Any number of the
+    /// tasks can be toggled or moved into another group.
+    /// Typing filters the tasks by a fuzzy match
+    fn open_group_prompt(&mut self, title: String) -> AppResult<()> {
+        self.all_tasks = self.storage_handler.get_all_tasks()?;
+        let single_tasks = self.storage_handler.get_tasks_by_group(Some(&title))?;
+        let single_tasks = self.filter_and_sort_tasks(single_tasks.into_iter().rev().collect())?;
+        let entries = build_tree(single_tasks, &self.collapsed);
+
+        let mut items = vec![
+            SelectItem::text("← back"),
+            SelectItem::text("☑ toggle tasks"),
+            SelectItem::text("⇄ move tasks"),
+        ];
+        for entry in entries.iter() {
+            items.push(SelectItem::task(&entry.task, self.format_entry(entry)));
+        }
+        let selection = fuzzy_select(&items, &Term::stderr());
+        match self.handle_select_error(selection)? {
+            0 => self.execute(),
+            1 => self.open_toggle_prompt(entries),
+            2 => MoveCommand::new()
+                .open_move_prompt(entries.into_iter().map(|entry| entry.task).collect()),
+            val => self.open_task_prompt(Some(title), &entries[val - 3]),
         }
     }
 }
@@ -81,45 +333,48 @@ impl ListCommand {
 impl Command for ListCommand {
     /// This method is called on command execution.
     /// Contains the main code of the command
-    fn execute(&mut self) {
-        let mut groups = self.storage_handler.get_all_groups();
-        let group_clone = groups.clone();
-        let mut single_tasks = self
-            .storage_handler
-            .get_all_tasks()
-            .into_iter()
-            .filter(|task| task.group == None)
-            .collect::<Vec<Task>>();
-
-        let mut items = (&single_tasks)
-            .into_iter()
-            .map(|x| self.get_checked_symbol(&x) + "  " + &x.title)
-            .collect::<Vec<String>>();
-        items.append(&mut groups);
+    fn execute(&mut self) -> AppResult<()> {
+        if self.arguments.has_flag("toggle") {
+            if !Term::stderr().is_term() {
+                return Err(AppError::Validation(
+                    "--toggle needs an interactive terminal".to_string(),
+                ));
+            }
+            let tasks = self.get_tasks_by_arguments()?;
+            return self.open_toggle_prompt(build_tree(tasks, &HashSet::new()));
+        }
+        if self.is_print_mode() {
+            return self.print_tasks();
+        }
+        self.all_tasks = self.storage_handler.get_all_tasks()?;
+        let groups = self.storage_handler.get_all_groups()?;
+        let group_count = groups.len();
+        let single_tasks = self.storage_handler.get_tasks_by_group(None)?;
+        let single_tasks = self.filter_and_sort_tasks(single_tasks.into_iter().rev().collect())?;
+        let entries = build_tree(single_tasks, &HashSet::new());
 
-        items = items.into_iter().rev().collect();
+        let mut items = groups
+            .iter()
+            .rev()
+            .map(|group| SelectItem::text(group))
+            .collect::<Vec<SelectItem>>();
+        for entry in entries.iter() {
+            items.push(SelectItem::task(&entry.task, self.format_entry(entry)));
+        }
+        items.push(SelectItem::text("☑ toggle tasks"));
+        items.push(SelectItem::text("⇄ move tasks"));
 
-        let selection = Select::with_theme(&ColorfulTheme::default())
-            .items(&items)
-            .default(0)
-            .interact_on_opt(&Term::stderr());
+        let selection = fuzzy_select(&items, &Term::stderr());
 
-        match self.handle_select_error(selection) {
-            None => println!("An error occurred while selecting"),
-            Some(val) => {
-                println!("{}", ((val as i32) - (group_clone.len() as i32)));
-                if ((val as i32) - (group_clone.len() as i32)) >= 0 && group_clone.len() != 0 {
-                    if !Confirm::new().with_prompt("Save?").interact().unwrap() {
-                        return;
-                    }
-                    let mut actual = (single_tasks[val-group_clone.len()]).clone();
-                    actual.finished = !actual.finished;
-                    single_tasks[val-group_clone.len()] = actual.clone();
-                    self.storage_handler.write_task_data(single_tasks);
-                } else {
-                    self.open_group_prompt(items[val].clone());
-                }
+        match self.handle_select_error(selection)? {
+            val if val < group_count => self.open_group_prompt(items[val].text.clone()),
+            val if val == items.len() - 2 => {
+                let tasks = self.filter_and_sort_tasks(self.all_tasks.clone())?;
+                self.open_toggle_prompt(build_tree(tasks, &HashSet::new()))
             }
+            val if val == items.len() - 1 => MoveCommand::new()
+                .open_move_prompt(entries.into_iter().map(|entry| entry.task).collect()),
+            val => self.open_task_prompt(None, &entries[val - group_count]),
         }
     }
 
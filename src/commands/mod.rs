@@ -1,5 +1,20 @@
 pub mod add_command;
+pub mod board_command;
 pub mod command_trait;
+pub mod delete_command;
+pub mod depend_command;
+pub mod doctor_command;
+pub mod done_command;
+pub mod edit_command;
+pub mod group_command;
 pub mod help_command;
 pub mod list_command;
-pub mod delete_command;
+pub mod move_command;
+pub mod next_command;
+pub mod search_command;
+pub mod show_command;
+pub mod tags_command;
+pub mod task_format;
+pub mod task_search;
+pub mod task_tree;
+pub mod tui_command;
@@ -0,0 +1,217 @@
+use crate::commands::command_trait::{Command, CommandInfo};
+use crate::commands::task_search::contains_text;
+use crate::error::{AppError, AppResult};
+use crate::inputs::arguments::Arguments;
+use crate::inputs::error_handling::InputErrorHandling;
+use crate::inputs::tags::TagFilter;
+use crate::storage_handler::{StorageHandler, Task};
+use dialoguer::console::Term;
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::{Confirm, MultiSelect, Select};
+
+/// Defines the move command that moves
+/// tasks into another group
+pub struct MoveCommand {
+    title: String,
+    description: String,
+    usage: String,
+    arguments: Arguments,
+    storage_handler: StorageHandler,
+}
+
+impl InputErrorHandling for MoveCommand {}
+
+impl MoveCommand {
+    /// Creates a new instance of the move command
+    /// and initializes the storage handler in it.
+    pub fn new() -> MoveCommand {
+        MoveCommand::with_storage(StorageHandler::new())
+    }
+
+    /// Creates a new instance of the move command
+    /// that uses the given storage handler
+    pub fn with_storage(storage_handler: StorageHandler) -> MoveCommand {
+        MoveCommand {
+            title: String::from("Move"),
+            description: String::from("Moves tasks into another group or removes them from their group"),
+            usage: String::from(
+                "move [id...] [+tag...] [-tag...] [--from <group>] [--search <text>] (--to <group>|--no-group) or just type the command",
+            ),
+            arguments: Arguments::default(),
+            storage_handler,
+        }
+    }
+
+    /// Parses the raw command line arguments and stores them in the command.
+    /// If tasks are selected by arguments, no task prompt is opened
+    pub fn with_arguments(mut self, raw: Vec<String>) -> MoveCommand {
+        self.arguments = Arguments::parse(raw, &["no-group"]);
+        self
+    }
+
+    /// Checks if the tasks are selected by IDs or filters
+    fn has_selection_arguments(&self) -> bool {
+        !self.arguments.positional().is_empty()
+            || self.arguments.has_flag("from")
+            || self.arguments.has_flag("search")
+    }
+
+    /// Gets all tasks that match the IDs and filters passed as arguments.
+    /// Positional values are task IDs or tag filters like +backend
+    fn get_tasks_by_arguments(&mut self) -> AppResult<Vec<Task>> {
+        let (ids, filters): (Vec<String>, Vec<String>) = self
+            .arguments
+            .positional()
+            .iter()
+            .cloned()
+            .partition(|value| value.parse::<u64>().is_ok());
+        let ids = ids
+            .iter()
+            .filter_map(|id| id.parse::<u64>().ok())
+            .collect::<Vec<u64>>();
+        let filter = TagFilter::parse(&filters)?;
+        let mut tasks = match self.arguments.flag_value("from") {
+            Some(group) => {
+                self.storage_handler.validate_group_exists(&group)?;
+                self.storage_handler.get_tasks_by_group(Some(&group))?
+            }
+            None => self.storage_handler.get_all_tasks()?,
+        };
+        let all_tasks = self.storage_handler.get_all_tasks()?;
+        if let Some(id) = ids
+            .iter()
+            .find(|id| !self.storage_handler.id_exists_in_vec(&all_tasks, **id))
+        {
+            return Err(AppError::NotFound(format!(
+                "There is no task with ID {}",
+                id
+            )));
+        }
+        let search = self.arguments.flag_value("search");
+        tasks.retain(|task| {
+            (ids.is_empty() || ids.contains(&task.id))
+                && filter.matches(task)
+                && search.as_ref().is_none_or(|text| contains_text(task, text))
+        });
+        Ok(tasks)
+    }
+
+    /// Gets the target group passed as argument. Returns None
+    /// if neither --to nor --no-group has been passed
+    fn get_target_argument(&mut self) -> AppResult<Option<Option<String>>> {
+        if self.arguments.has_flag("no-group") {
+            return Ok(Some(None));
+        }
+        match self.arguments.flag_value("to") {
+            Some(group) => {
+                self.storage_handler.validate_group_exists(&group)?;
+                Ok(Some(Some(group)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Opens a prompt for selecting the group
+    /// the tasks are moved into
+    fn open_target_prompt(&mut self) -> AppResult<Option<String>> {
+        let mut items = vec!["❌ no group".to_string()];
+        items.extend(self.storage_handler.get_all_groups()?);
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Move to")
+            .items(&items)
+            .default(0)
+            .interact_on_opt(&Term::stderr());
+        match self.handle_select_error(selection)? {
+            0 => Ok(None),
+            val => Ok(Some(items[val].clone())),
+        }
+    }
+
+    /// Moves all given tasks into the group and
+    /// saves them with a single write
+    fn move_tasks(&mut self, tasks: Vec<Task>, group: Option<String>) -> AppResult<()> {
+        let count = tasks.len();
+        let moved = tasks
+            .into_iter()
+            .map(|task| Task {
+                group: group.clone(),
+                ..task
+            })
+            .collect::<Vec<Task>>();
+        self.storage_handler.write_task_data(moved)?;
+        println!(
+            "Moved {} tasks to {}",
+            count,
+            group.as_deref().unwrap_or("no group")
+        );
+        Ok(())
+    }
+
+    /// Opens a prompt for selecting any number of the given tasks and the
+    /// group they are moved into. The tasks are moved after a confirmation
+    pub fn open_move_prompt(&mut self, tasks: Vec<Task>) -> AppResult<()> {
+        if tasks.is_empty() {
+            println!("No tasks given");
+            return Ok(());
+        }
+        let items = tasks
+            .iter()
+            .map(|task| match &task.group {
+                Some(group) => task.title.clone() + " (" + group + ")",
+                None => task.title.clone(),
+            })
+            .collect::<Vec<String>>();
+        let selection = MultiSelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Tasks to move (space to select)")
+            .items(&items)
+            .interact_on_opt(&Term::stderr())?;
+        let selected = match selection {
+            Some(selected) if !selected.is_empty() => selected,
+            _ => return Err(AppError::Cancelled),
+        };
+        let group = self.open_target_prompt()?;
+        let confirmation = Confirm::new().with_prompt("Save?").interact();
+        self.handle_confirm_error(confirmation)?;
+        let tasks = selected
+            .into_iter()
+            .map(|index| tasks[index].clone())
+            .collect();
+        self.move_tasks(tasks, group)
+    }
+}
+
+impl Command for MoveCommand {
+    /// This method is called on command execution.
+    /// Contains the main code of the command
+    fn execute(&mut self) -> AppResult<()> {
+        if !self.has_selection_arguments() {
+            let tasks = self.storage_handler.get_all_tasks()?;
+            return self.open_move_prompt(tasks);
+        }
+        let tasks = self.get_tasks_by_arguments()?;
+        if tasks.is_empty() {
+            return Err(AppError::NotFound(
+                "No tasks match the arguments".to_string(),
+            ));
+        }
+        let group = match self.get_target_argument()? {
+            Some(group) => group,
+            None if Term::stderr().is_term() => self.open_target_prompt()?,
+            None => {
+                return Err(AppError::Validation(
+                    "Pass --to <group> or --no-group".to_string(),
+                ));
+            }
+        };
+        self.move_tasks(tasks, group)
+    }
+
+    /// Gets the base command information
+    fn get_command_info(&mut self) -> CommandInfo {
+        CommandInfo {
+            title: self.title.clone(),
+            description: self.description.clone(),
+            usage: self.usage.clone(),
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::commands::command_trait::{Command, CommandInfo};
+use crate::commands::task_format::format_task;
+use crate::error::AppResult;
+use crate::inputs::arguments::Arguments;
+use crate::inputs::tags::TagFilter;
+use crate::storage_handler::status::Status;
+use crate::storage_handler::{StorageHandler, Task};
+use std::cmp::Reverse;
+
+/// Defines the next command that lists all
+/// tasks that can be worked on right now
+pub struct NextCommand {
+    title: String,
+    description: String,
+    usage: String,
+    arguments: Arguments,
+    storage_handler: StorageHandler,
+}
+
+impl NextCommand {
+    /// Creates a new instance of the next command
+    /// and initializes the storage handler in it.
+    pub fn new() -> NextCommand {
+        NextCommand::with_storage(StorageHandler::new())
+    }
+
+    /// Creates a new instance of the next command
+    /// that uses the given storage handler
+    pub fn with_storage(storage_handler: StorageHandler) -> NextCommand {
+        NextCommand {
+            title: String::from("Next"),
+            description: String::from(
+                "Lists all open tasks that are not blocked by other tasks or open subtasks",
+            ),
+            usage: String::from("next [+tag...] [-tag...]"),
+            arguments: Arguments::default(),
+            storage_handler,
+        }
+    }
+
+    /// Parses the raw command line arguments and stores them in the command
+    pub fn with_arguments(mut self, raw: Vec<String>) -> NextCommand {
+        self.arguments = Arguments::parse(raw, &[]);
+        self
+    }
+
+    /// Checks if the task can be worked on. It must not be closed, blocked,
+    /// waiting, depend on open tasks or have open subtasks
+    fn is_actionable(&self, task: &Task, tasks: &[Task]) -> bool {
+        !task.is_closed()
+            && !matches!(task.status, Status::Blocked | Status::Waiting)
+            && task.open_blockers(tasks).is_empty()
+            && !tasks
+                .iter()
+                .any(|child| child.parent == Some(task.id) && !child.is_closed())
+    }
+}
+
+impl Command for NextCommand {
+    /// This method is called on command execution.
+    /// Contains the main code of the command
+    fn execute(&mut self) -> AppResult<()> {
+        let filter = TagFilter::parse(self.arguments.positional())?;
+        let tasks = self.storage_handler.get_all_tasks()?;
+        let mut actionable = tasks
+            .iter()
+            .filter(|task| filter.matches(task) && self.is_actionable(task, &tasks))
+            .collect::<Vec<&Task>>();
+        actionable.sort_by_key(|task| (Reverse(task.priority), task.due.is_none(), task.due));
+        if actionable.is_empty() {
+            println!("No tasks given");
+        }
+        for task in actionable {
+            let mut line = format!("{:>4}  {}", task.id, format_task(task, &tasks));
+            if let Some(name) = &task.group {
+                line = line + " (" + name + ")";
+            }
+            println!("{}", line);
+        }
+        Ok(())
+    }
+
+    /// Gets the base command information
+    fn get_command_info(&mut self) -> CommandInfo {
+        CommandInfo {
+            title: self.title.clone(),
+            description: self.description.clone(),
+            usage: self.usage.clone(),
+        }
+    }
+}
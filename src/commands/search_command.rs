@@ -0,0 +1,92 @@
+use crate::commands::command_trait::{Command, CommandInfo};
+use crate::commands::task_format::format_task;
+use crate::commands::task_search::search_tasks;
+use crate::error::{AppError, AppResult};
+use crate::inputs::arguments::Arguments;
+use crate::storage_handler::StorageHandler;
+
+/// Defines the search command that finds tasks
+/// by a fuzzy query
+pub struct SearchCommand {
+    title: String,
+    description: String,
+    usage: String,
+    arguments: Arguments,
+    storage_handler: StorageHandler,
+}
+
+impl SearchCommand {
+    /// Creates a new instance of the search command
+    /// and initializes the storage handler in it.
+    pub fn new() -> SearchCommand {
+        SearchCommand::with_storage(StorageHandler::new())
+    }
+
+    /// Creates a new instance of the search command
+    /// that uses the given storage handler
+    pub fn with_storage(storage_handler: StorageHandler) -> SearchCommand {
+        SearchCommand {
+            title: String::from("Search"),
+            description: String::from(
+                "Finds tasks by a fuzzy query on their title, group and notes. The best matches are listed first",
+            ),
+            usage: String::from("search <query> [--open] [--limit <n>]"),
+            arguments: Arguments::default(),
+            storage_handler,
+        }
+    }
+
+    /// Parses the raw command line arguments and stores them in the command
+    pub fn with_arguments(mut self, raw: Vec<String>) -> SearchCommand {
+        self.arguments = Arguments::parse(raw, &["open"]);
+        self
+    }
+
+    /// Gets the maximum number of results passed with --limit
+    fn get_limit_argument(&self) -> AppResult<usize> {
+        match self.arguments.flag_value("limit") {
+            Some(value) => value
+                .parse::<usize>()
+                .map_err(|_| AppError::Validation(format!("{} is not a valid limit", value))),
+            None => Ok(usize::MAX),
+        }
+    }
+}
+
+impl Command for SearchCommand {
+    /// This method is called on command execution.
+    /// Contains the main code of the command
+    fn execute(&mut self) -> AppResult<()> {
+        let query = self.arguments.positional().join(" ");
+        if query.trim().is_empty() {
+            return Err(AppError::Validation(format!("Usage: {}", self.usage)));
+        }
+        let limit = self.get_limit_argument()?;
+        let all_tasks = self.storage_handler.get_all_tasks()?;
+        let mut tasks = all_tasks.clone();
+        if self.arguments.has_flag("open") {
+            tasks.retain(|task| !task.is_closed());
+        }
+        let results = search_tasks(tasks, &query);
+        if results.is_empty() {
+            return Err(AppError::NotFound(format!("No tasks match {}", query)));
+        }
+        for task in results.iter().take(limit) {
+            let mut line = format!("{:>4}  {}", task.id, format_task(task, &all_tasks));
+            if let Some(name) = &task.group {
+                line = line + " (" + name + ")";
+            }
+            println!("{}", line);
+        }
+        Ok(())
+    }
+
+    /// Gets the base command information
+    fn get_command_info(&mut self) -> CommandInfo {
+        CommandInfo {
+            title: self.title.clone(),
+            description: self.description.clone(),
+            usage: self.usage.clone(),
+        }
+    }
+}
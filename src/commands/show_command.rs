@@ -0,0 +1,145 @@
+use crate::commands::command_trait::{Command, CommandInfo};
+use crate::commands::task_format::format_time;
+use crate::error::{AppError, AppResult};
+use crate::inputs::arguments::Arguments;
+use crate::inputs::error_handling::InputErrorHandling;
+use crate::inputs::notes;
+use crate::storage_handler::{StorageHandler, Task};
+use dialoguer::console::Term;
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::Select;
+
+/// Defines the show command that prints
+/// all details of a single task
+pub struct ShowCommand {
+    title: String,
+    description: String,
+    usage: String,
+    arguments: Arguments,
+    storage_handler: StorageHandler,
+}
+
+impl InputErrorHandling for ShowCommand {}
+
+impl ShowCommand {
+    /// Creates a new instance of the show command
+    /// and initializes the storage handler in it.
+    pub fn new() -> ShowCommand {
+        ShowCommand::with_storage(StorageHandler::new())
+    }
+
+    /// Creates a new instance of the show command
+    /// that uses the given storage handler
+    pub fn with_storage(storage_handler: StorageHandler) -> ShowCommand {
+        ShowCommand {
+            title: String::from("Show"),
+            description: String::from(
+                "Shows all details and notes of a task. With --edit the notes can be changed",
+            ),
+            usage: String::from("show [id] [--edit]"),
+            arguments: Arguments::default(),
+            storage_handler,
+        }
+    }
+
+    /// Parses the raw command line arguments and stores them in the command.
+    /// If an ID is passed, the task is shown without any prompt
+    pub fn with_arguments(mut self, raw: Vec<String>) -> ShowCommand {
+        self.arguments = Arguments::parse(raw, &["edit"]);
+        self
+    }
+
+    /// Gets the task by the ID passed as argument or
+    /// opens a prompt for selecting the task
+    fn get_task(&mut self) -> AppResult<Task> {
+        if let Some(id) = self.arguments.ids()?.first() {
+            return self
+                .storage_handler
+                .get_task_by_id(*id)?
+                .ok_or_else(|| AppError::NotFound(format!("There is no task with ID {}", id)));
+        }
+        let tasks = self.storage_handler.get_all_tasks()?;
+        if tasks.is_empty() {
+            return Err(AppError::NotFound("No tasks given".to_string()));
+        }
+        let items = tasks
+            .iter()
+            .map(|task| task.title.clone())
+            .collect::<Vec<String>>();
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .items(&items)
+            .default(0)
+            .interact_on_opt(&Term::stderr());
+        let val = self.handle_select_error(selection)?;
+        Ok(tasks[val].clone())
+    }
+}
+
+impl Command for ShowCommand {
+    /// This method is called on command execution.
+    /// Contains the main code of the command
+    fn execute(&mut self) -> AppResult<()> {
+        let mut task = self.get_task()?;
+        if self.arguments.has_flag("edit") {
+            task.notes = notes::prompt_notes(task.notes.as_deref())?;
+            self.storage_handler.write_task_data(vec![task.clone()])?;
+        }
+        println!("ID:       {}", task.id);
+        println!("TITLE:    {}", task.title);
+        println!("STATUS:   {}", task.status.name());
+        println!("PRIORITY: {}", task.priority.name());
+        if let Some(group) = &task.group {
+            println!("GROUP:    {}", group);
+        }
+        if let Some(parent) = task.parent {
+            println!("PARENT:   {}", parent);
+        }
+        if let Some(due) = task.due {
+            println!("DUE:      {}", due);
+        }
+        println!("CREATED:  {}", format_time(task.created_at));
+        println!("UPDATED:  {}", format_time(task.updated_at));
+        if task.completed_at.is_some() {
+            println!("COMPLETED:{}", format_time(task.completed_at));
+        }
+        if let Some(recurrence) = &task.recurrence {
+            println!("REPEATS:  {}", recurrence);
+        }
+        if !task.tags.is_empty() {
+            let tags = task
+                .tags
+                .iter()
+                .map(|tag| format!("+{}", tag))
+                .collect::<Vec<String>>();
+            println!("TAGS:     {}", tags.join(" "));
+        }
+        if !task.depends_on.is_empty() {
+            let ids = task
+                .depends_on
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<String>>();
+            println!("DEPENDS:  {}", ids.join(", "));
+        }
+        let children = self.storage_handler.get_children(task.id)?;
+        if !children.is_empty() {
+            println!("SUBTASKS:");
+            for child in children.iter() {
+                println!("{:>4}  {} {}", child.id, child.status.symbol(), child.title);
+            }
+        }
+        if let Some(notes) = &task.notes {
+            println!("\n{}", notes);
+        }
+        Ok(())
+    }
+
+    /// Gets the base command information
+    fn get_command_info(&mut self) -> CommandInfo {
+        CommandInfo {
+            title: self.title.clone(),
+            description: self.description.clone(),
+            usage: self.usage.clone(),
+        }
+    }
+}
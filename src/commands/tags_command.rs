@@ -0,0 +1,54 @@
+use crate::commands::command_trait::{Command, CommandInfo};
+use crate::error::AppResult;
+use crate::storage_handler::StorageHandler;
+
+/// Defines the tags command that lists all tags
+pub struct TagsCommand {
+    title: String,
+    description: String,
+    usage: String,
+    storage_handler: StorageHandler,
+}
+
+impl TagsCommand {
+    /// Creates a new instance of the tags command
+    /// and initializes the storage handler in it.
+    pub fn new() -> TagsCommand {
+        TagsCommand::with_storage(StorageHandler::new())
+    }
+
+    /// Creates a new instance of the tags command
+    /// that uses the given storage handler
+    pub fn with_storage(storage_handler: StorageHandler) -> TagsCommand {
+        TagsCommand {
+            title: String::from("Tags"),
+            description: String::from("Lists all tags with the number of tasks using them"),
+            usage: String::from("Just type tags"),
+            storage_handler,
+        }
+    }
+}
+
+impl Command for TagsCommand {
+    /// This method is called on command execution.
+    /// Contains the main code of the command
+    fn execute(&mut self) -> AppResult<()> {
+        let counts = self.storage_handler.get_tag_counts()?;
+        if counts.is_empty() {
+            println!("No tags given");
+        }
+        for (tag, count) in counts {
+            println!("{:>4}  +{}", count, tag);
+        }
+        Ok(())
+    }
+
+    /// Gets the base command information
+    fn get_command_info(&mut self) -> CommandInfo {
+        CommandInfo {
+            title: self.title.clone(),
+            description: self.description.clone(),
+            usage: self.usage.clone(),
+        }
+    }
+}
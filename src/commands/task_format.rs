@@ -0,0 +1,139 @@
+use crate::inputs::due_date;
+use crate::storage_handler::status::Status;
+use crate::storage_handler::{Priority, Task};
+use chrono::{DateTime, Local, Utc};
+use dialoguer::console::style;
+
+/// Gets the symbol of the status of the task. Open tasks
+/// that wait for other tasks are shown as blocked
+pub fn get_checked_symbol(task: &Task, tasks: &[Task]) -> String {
+    if !task.is_closed() && !task.open_blockers(tasks).is_empty() {
+        return Status::Blocked.symbol().to_string();
+    }
+    task.status.symbol().to_string()
+}
+
+/// Defines how a segment of a formatted task is highlighted.
+/// Every output maps the highlights to its own styles
+#[derive(Clone, Copy, PartialEq)]
+pub enum Highlight {
+    Plain,
+    Dimmed,
+    Tag,
+    Warning,
+    Alert,
+}
+
+/// Splits the task into the segments of its line with the symbol, priority,
+/// title, due date, tags, blockers, recurrence and notes marker. Overdue tasks
+/// and the tasks that block the task are highlighted
+pub fn task_segments(task: &Task, tasks: &[Task]) -> Vec<(String, Highlight)> {
+    let priority = match task.priority {
+        Priority::Urgent => Highlight::Alert,
+        Priority::High => Highlight::Warning,
+        _ => Highlight::Plain,
+    };
+    let title = match task.is_closed() {
+        true => Highlight::Dimmed,
+        false => Highlight::Plain,
+    };
+    let mut segments = vec![
+        (get_checked_symbol(task, tasks) + " ", Highlight::Plain),
+        (format!("{:<4}", task.priority.marker()), priority),
+        (" ".to_string(), Highlight::Plain),
+        (task.title.clone(), title),
+    ];
+    match task.due {
+        Some(due) if !task.is_closed() && due < due_date::today() => {
+            segments.push((format!("  overdue since {}", due), Highlight::Alert));
+        }
+        Some(due) => segments.push((format!("  due {}", due), Highlight::Plain)),
+        None => {}
+    }
+    for tag in task.tags.iter() {
+        segments.push((format!("  +{}", tag), Highlight::Tag));
+    }
+    let blockers = task.open_blockers(tasks);
+    if !task.is_closed() && !blockers.is_empty() {
+        let ids = blockers
+            .iter()
+            .map(|blocker| blocker.id.to_string())
+            .collect::<Vec<String>>();
+        segments.push((
+            format!("  blocked by {}", ids.join(", ")),
+            Highlight::Warning,
+        ));
+    }
+    if let Some(recurrence) = &task.recurrence {
+        segments.push((format!("  ↻ {}", recurrence), Highlight::Plain));
+    }
+    if task.notes.is_some() {
+        segments.push(("  ✎".to_string(), Highlight::Plain));
+    }
+    segments
+}
+
+/// Formats the task as a single line for the terminal
+pub fn format_task(task: &Task, tasks: &[Task]) -> String {
+    task_segments(task, tasks)
+        .into_iter()
+        .map(|(text, highlight)| match highlight {
+            Highlight::Plain => text,
+            Highlight::Dimmed => style(text).dim().to_string(),
+            Highlight::Tag => style(text).cyan().to_string(),
+            Highlight::Warning => style(text).yellow().to_string(),
+            Highlight::Alert => style(text).red().bold().to_string(),
+        })
+        .collect()
+}
+
+/// Formats the time in the local timezone.
+/// Times that have not been recorded are shown as unknown
+pub fn format_time(time: Option<DateTime<Utc>>) -> String {
+    match time {
+        Some(time) => time
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string(),
+        None => "unknown".to_string(),
+    }
+}
+
+/// Prints the next occurrences that have been
+/// added for completed recurring tasks
+pub fn print_occurrences(occurrences: &[Task]) {
+    for task in occurrences.iter() {
+        if let Some(due) = task.due {
+            println!(
+                "Added the next occurrence of {} due {} with ID {}",
+                task.title, due, task.id
+            );
+        }
+    }
+}
+
+/// Gets the warning for a task that is done while the tasks
+/// it depends on are still open. Returns None otherwise
+pub fn get_blocker_warning(task: &Task, tasks: &[Task]) -> Option<String> {
+    let blockers = task.open_blockers(tasks);
+    if task.status != Status::Done || blockers.is_empty() {
+        return None;
+    }
+    let titles = blockers
+        .iter()
+        .map(|blocker| blocker.title.clone())
+        .collect::<Vec<String>>();
+    Some(format!(
+        "The task {} is still blocked by {}",
+        task.title,
+        titles.join(", ")
+    ))
+}
+
+/// Prints a warning if the task is done
+/// while the tasks it depends on are still open
+pub fn warn_open_blockers(task: &Task, tasks: &[Task]) {
+    if let Some(warning) = get_blocker_warning(task, tasks) {
+        eprintln!("WARNING: {}", warning);
+    }
+}
@@ -0,0 +1,216 @@
+use crate::storage_handler::Task;
+use dialoguer::console::{strip_ansi_codes, style, Key, Term};
+use dialoguer::theme::{ColorfulTheme, Theme};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use std::cmp::Reverse;
+use std::io;
+
+/// Defines an item of the fuzzy select. Items of tasks are
+/// ranked by the task, all others by their visible text
+pub struct SelectItem<'a> {
+    pub text: String,
+    pub task: Option<&'a Task>,
+}
+
+impl<'a> SelectItem<'a> {
+    /// Creates an item that is matched by its text
+    pub fn text(text: &str) -> SelectItem<'a> {
+        SelectItem {
+            text: text.to_string(),
+            task: None,
+        }
+    }
+
+    /// Creates an item that shows the line of the task
+    /// but is matched by its title, group and notes
+    pub fn task(task: &'a Task, line: String) -> SelectItem<'a> {
+        SelectItem {
+            text: line,
+            task: Some(task),
+        }
+    }
+}
+
+/// Gets the fuzzy score of the task for the query. Matches in the title
+/// rank higher than matches in the group, which rank higher than
+/// matches in the notes. Returns None if nothing matches
+pub fn score_task(matcher: &SkimMatcherV2, task: &Task, query: &str) -> Option<i64> {
+    let title = matcher
+        .fuzzy_match(&task.title, query)
+        .map(|score| score * 3);
+    let group = task
+        .group
+        .as_ref()
+        .and_then(|group| matcher.fuzzy_match(group, query))
+        .map(|score| score * 2);
+    let notes = task
+        .notes
+        .as_ref()
+        .and_then(|notes| matcher.fuzzy_match(notes, query));
+    [title, group, notes].into_iter().flatten().max()
+}
+
+/// Checks if the title or the notes of the task contain
+/// the text. The case of the letters is ignored
+pub fn contains_text(task: &Task, text: &str) -> bool {
+    let text = text.to_lowercase();
+    task.title.to_lowercase().contains(&text)
+        || task
+            .notes
+            .as_ref()
+            .is_some_and(|notes| notes.to_lowercase().contains(&text))
+}
+
+/// Removes all tasks that do not match the query and sorts the
+/// others by their score, starting with the best match.
+/// Tasks with the same score keep their order
+pub fn search_tasks(tasks: Vec<Task>, query: &str) -> Vec<Task> {
+    let matcher = SkimMatcherV2::default().ignore_case();
+    let mut ranked = tasks
+        .into_iter()
+        .filter_map(|task| score_task(&matcher, &task, query).map(|score| (score, task)))
+        .collect::<Vec<(i64, Task)>>();
+    ranked.sort_by_key(|(score, _)| Reverse(*score));
+    ranked.into_iter().map(|(_, task)| task).collect()
+}
+
+/// Gets the positions of the items that match the query, starting with the
+/// best match. Tasks are scored like in the search, the text of all other
+/// items counts like a title. An empty query keeps all items in their order
+pub fn rank_items(items: &[SelectItem], query: &str) -> Vec<usize> {
+    let matcher = SkimMatcherV2::default().ignore_case();
+    let mut ranked = items
+        .iter()
+        .enumerate()
+        .filter_map(|(index, item)| {
+            let score = match item.task {
+                _ if query.is_empty() => Some(0),
+                Some(task) => score_task(&matcher, task, query),
+                None => matcher
+                    .fuzzy_match(&strip_ansi_codes(&item.text), query)
+                    .map(|score| score * 3),
+            };
+            score.map(|score| (score, index))
+        })
+        .collect::<Vec<(i64, usize)>>();
+    ranked.sort_by_key(|(score, _)| Reverse(*score));
+    ranked.into_iter().map(|(_, index)| index).collect()
+}
+
+/// Opens a select whose items are filtered and ranked while typing.
+/// Returns the position of the selected item or None if the
+/// select has been closed with escape. Fails without a terminal
+pub fn fuzzy_select(items: &[SelectItem], term: &Term) -> io::Result<Option<usize>> {
+    if !term.is_term() {
+        return Err(not_a_terminal());
+    }
+    let theme = ColorfulTheme::default();
+    let rows = (term.size().0 as usize).max(3) - 2;
+    let mut query = String::new();
+    let (mut selected, mut first, mut drawn) = (0, 0, 0);
+    term.hide_cursor()?;
+    let result = loop {
+        let ranked = rank_items(items, &query);
+        selected = selected.min(ranked.len().saturating_sub(1));
+        first = first.min(selected).max((selected + 1).saturating_sub(rows));
+        term.clear_last_lines(drawn)?;
+        let mut lines = vec![format!("{} {}", style("Search:").bold(), query)];
+        for (position, index) in ranked.iter().enumerate().skip(first).take(rows) {
+            let mut line = String::new();
+            theme
+                .format_select_prompt_item(&mut line, &items[*index].text, position == selected)
+                .map_err(io::Error::other)?;
+            lines.push(line);
+        }
+        for line in lines.iter() {
+            term.write_line(line)?;
+        }
+        drawn = lines.len();
+        match term.read_key()? {
+            Key::Unknown => break Err(not_a_terminal()),
+            Key::Escape => break Ok(None),
+            Key::Enter if !ranked.is_empty() => break Ok(Some(ranked[selected])),
+            Key::ArrowUp if !ranked.is_empty() => {
+                selected = selected.checked_sub(1).unwrap_or(ranked.len() - 1)
+            }
+            Key::ArrowDown if !ranked.is_empty() => selected = (selected + 1) % ranked.len(),
+            Key::Backspace => {
+                query.pop();
+                selected = 0;
+            }
+            Key::Char(char) if !char.is_control() => {
+                query.push(char);
+                selected = 0;
+            }
+            _ => {}
+        }
+    };
+    term.clear_last_lines(drawn)?;
+    term.show_cursor()?;
+    result
+}
+
+/// Creates the error of selects that cannot read keys
+/// because no interactive terminal is attached
+fn not_a_terminal() -> io::Error {
+    io::Error::new(io::ErrorKind::NotConnected, "not a terminal")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(title: &str, notes: Option<&str>) -> Task {
+        Task {
+            notes: notes.map(|notes| notes.to_string()),
+            ..Task::new(title.to_string(), None)
+        }
+    }
+
+    #[test]
+    fn rank_items_matches_the_notes_of_tasks() {
+        let tasks = [
+            task("Fix login", None),
+            task("Call Anna", Some("about the invoice")),
+        ];
+        let items = vec![
+            SelectItem::text("📁 invoices"),
+            SelectItem::task(&tasks[0], "Fix login".to_string()),
+            SelectItem::task(&tasks[1], "Call Anna".to_string()),
+        ];
+        assert_eq!(rank_items(&items, "invoice"), vec![0, 2]);
+    }
+
+    #[test]
+    fn rank_items_puts_title_matches_first() {
+        let tasks = [
+            task("Plan trip", Some("review budget")),
+            task("Review budget", None),
+        ];
+        let items = vec![
+            SelectItem::task(&tasks[0], "Plan trip".to_string()),
+            SelectItem::task(&tasks[1], "Review budget".to_string()),
+        ];
+        assert_eq!(rank_items(&items, "review budget"), vec![1, 0]);
+    }
+
+    #[test]
+    fn rank_items_keeps_all_items_without_query() {
+        let tasks = [task("Plan trip", None)];
+        let items = vec![
+            SelectItem::text("← back"),
+            SelectItem::task(&tasks[0], "Plan trip".to_string()),
+        ];
+        assert_eq!(rank_items(&items, ""), vec![0, 1]);
+    }
+
+    #[test]
+    fn fuzzy_select_fails_without_a_terminal() {
+        let null = std::fs::File::open("/dev/null").unwrap();
+        let term = Term::read_write_pair(null.try_clone().unwrap(), null);
+        let items = vec![SelectItem::text("a")];
+        let error = fuzzy_select(&items, &term).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotConnected);
+    }
+}
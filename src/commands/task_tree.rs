@@ -0,0 +1,56 @@
+use crate::storage_handler::Task;
+use std::collections::{HashMap, HashSet};
+
+/// Defines a single task in the tree of tasks
+/// with its depth and the state of its subtasks
+pub struct TreeEntry {
+    pub task: Task,
+    pub depth: usize,
+    pub children: usize,
+    pub closed_children: usize,
+}
+
+/// Orders the tasks as a tree, so that all subtasks follow their parent.
+/// Tasks whose parent is not in the list are shown on the top level.
+/// Subtasks of collapsed tasks are left out. The order of the tasks
+/// on each level is kept
+pub fn build_tree(tasks: Vec<Task>, collapsed: &HashSet<u64>) -> Vec<TreeEntry> {
+    let ids = tasks.iter().map(|task| task.id).collect::<HashSet<u64>>();
+    let mut children: HashMap<u64, Vec<Task>> = HashMap::new();
+    let mut roots = vec![];
+    for task in tasks {
+        match task.parent {
+            Some(parent) if ids.contains(&parent) => children.entry(parent).or_default().push(task),
+            _ => roots.push(task),
+        }
+    }
+    let mut entries = vec![];
+    for root in roots {
+        add_entry(root, 0, &mut children, collapsed, &mut entries);
+    }
+    entries
+}
+
+/// Adds the task and all its visible subtasks to the entries
+fn add_entry(
+    task: Task,
+    depth: usize,
+    children: &mut HashMap<u64, Vec<Task>>,
+    collapsed: &HashSet<u64>,
+    entries: &mut Vec<TreeEntry>,
+) {
+    let subtasks = children.remove(&task.id).unwrap_or_default();
+    let id = task.id;
+    entries.push(TreeEntry {
+        depth,
+        children: subtasks.len(),
+        closed_children: subtasks.iter().filter(|child| child.is_closed()).count(),
+        task,
+    });
+    if collapsed.contains(&id) {
+        return;
+    }
+    for subtask in subtasks {
+        add_entry(subtask, depth + 1, children, collapsed, entries);
+    }
+}
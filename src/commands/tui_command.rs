@@ -0,0 +1,48 @@
+use crate::commands::command_trait::{Command, CommandInfo};
+use crate::error::{AppError, AppResult};
+use crate::tui;
+use dialoguer::console::Term;
+
+/// Defines the tui command that opens the
+/// full-screen interface
+pub struct TuiCommand {
+    title: String,
+    description: String,
+    usage: String,
+}
+
+impl TuiCommand {
+    /// Creates a new instance of the tui command
+    /// with the default values.
+    pub fn new() -> TuiCommand {
+        TuiCommand {
+            title: String::from("Tui"),
+            description: String::from(
+                "Opens a full-screen interface that stays open and shows changes of other instances",
+            ),
+            usage: String::from("Just type tui"),
+        }
+    }
+}
+
+impl Command for TuiCommand {
+    /// This method is called on command execution.
+    /// Contains the main code of the command
+    fn execute(&mut self) -> AppResult<()> {
+        if !Term::stdout().is_term() {
+            return Err(AppError::Validation(
+                "The tui needs an interactive terminal".to_string(),
+            ));
+        }
+        tui::run()
+    }
+
+    /// Gets the base command information
+    fn get_command_info(&mut self) -> CommandInfo {
+        CommandInfo {
+            title: self.title.clone(),
+            description: self.description.clone(),
+            usage: self.usage.clone(),
+        }
+    }
+}
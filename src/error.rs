@@ -0,0 +1,75 @@
+use std::fmt;
+use std::io;
+
+/// Defines all errors that can occur while
+/// executing a command of the cli
+#[derive(Debug)]
+pub enum AppError {
+    /// Reading or writing a file failed
+    Io(io::Error),
+    /// The stored data could not be parsed
+    Parse(String),
+    /// The sqlite database returned an error
+    Database(rusqlite::Error),
+    /// The requested task or group does not exist
+    NotFound(String),
+    /// The user input or stored data is invalid
+    Validation(String),
+    /// Another instance holds the lock of the data directory
+    Locked(String),
+    /// The action has been cancelled by the user
+    Cancelled,
+}
+
+/// Result type that is used by all fallible operations of the cli
+pub type AppResult<T> = Result<T, AppError>;
+
+impl AppError {
+    /// Gets the exit code of the process that
+    /// belongs to the type of the error
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            AppError::Validation(_) => 2,
+            AppError::NotFound(_) => 3,
+            AppError::Parse(_) => 4,
+            AppError::Io(_) => 5,
+            AppError::Database(_) => 6,
+            AppError::Locked(_) => 7,
+            AppError::Cancelled => 130,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::Io(e) => write!(f, "Cannot access the data files: {}", e),
+            AppError::Parse(message) => write!(f, "Cannot parse the stored data: {}", message),
+            AppError::Database(e) => write!(f, "Database error: {}", e),
+            AppError::NotFound(message) => write!(f, "{}", message),
+            AppError::Validation(message) => write!(f, "{}", message),
+            AppError::Locked(message) => write!(f, "{}", message),
+            AppError::Cancelled => write!(f, "Cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<io::Error> for AppError {
+    fn from(e: io::Error) -> Self {
+        AppError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(e: serde_json::Error) -> Self {
+        AppError::Parse(e.to_string())
+    }
+}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(e: rusqlite::Error) -> Self {
+        AppError::Database(e)
+    }
+}
@@ -1,21 +1,35 @@
 use crate::commands::add_command::AddCommand;
+use crate::commands::board_command::BoardCommand;
+use crate::commands::delete_command::DeleteCommand;
+use crate::commands::depend_command::DependCommand;
+use crate::commands::doctor_command::DoctorCommand;
+use crate::commands::done_command::DoneCommand;
+use crate::commands::edit_command::EditCommand;
+use crate::commands::group_command::GroupCommand;
 use crate::commands::help_command::HelpCommand;
 use crate::commands::list_command::ListCommand;
-use crate::handler::commands;
+use crate::commands::move_command::MoveCommand;
+use crate::commands::next_command::NextCommand;
+use crate::commands::search_command::SearchCommand;
+use crate::commands::show_command::ShowCommand;
+use crate::commands::tags_command::TagsCommand;
+use crate::commands::tui_command::TuiCommand;
+use crate::error::AppResult;
 use crate::handler::commands::Commands;
 use crate::Command;
 use std::env::Args;
-use crate::commands::delete_command::DeleteCommand;
 
 pub struct CommandHandler {
-    arguments: Args,
+    arguments: Vec<String>,
 }
 
 impl CommandHandler {
     /// Creates a new command handler that can handle
     /// the command line input by default
     pub fn new(args: Args) -> CommandHandler {
-        CommandHandler { arguments: args }
+        CommandHandler {
+            arguments: args.collect(),
+        }
     }
 
     /// Parses the string of the command into the enum with
@@ -27,6 +41,18 @@ impl CommandHandler {
             "list" => Some(Commands::List),
             "add" => Some(Commands::Add),
             "delete" => Some(Commands::Delete),
+            "done" => Some(Commands::Done),
+            "doctor" | "repair" => Some(Commands::Doctor),
+            "tags" => Some(Commands::Tags),
+            "show" => Some(Commands::Show),
+            "next" => Some(Commands::Next),
+            "depend" => Some(Commands::Depend),
+            "edit" => Some(Commands::Edit),
+            "group" => Some(Commands::Group),
+            "move" => Some(Commands::Move),
+            "tui" => Some(Commands::Tui),
+            "board" => Some(Commands::Board),
+            "search" => Some(Commands::Search),
             _ => None,
         }
     }
@@ -35,21 +61,40 @@ impl CommandHandler {
     /// parses the command into the enum of all existing
     /// commands.
     pub fn get_command(&mut self) -> Option<Commands> {
-        match self.arguments.nth(1) {
+        match self.arguments.get(1).cloned() {
             Some(command) => self.parse_command_to_enum(command.as_str()),
             None => None,
         }
     }
 
+    /// Gets all arguments that are passed after the
+    /// command name itself
+    fn get_command_arguments(&mut self) -> Vec<String> {
+        self.arguments.iter().skip(2).cloned().collect()
+    }
+
     /// Executes the provided command by the enum.
     /// All other user inputs are handled by the command
     /// object itself.
-    pub fn execute_by_enum(&mut self, cmd: Commands) {
+    pub fn execute_by_enum(&mut self, cmd: Commands) -> AppResult<()> {
+        let args = self.get_command_arguments();
         match cmd {
             Commands::Help => HelpCommand::new().execute(),
-            Commands::List => ListCommand::new().execute(),
-            Commands::Add => AddCommand::new().execute(),
-            Commands::Delete => DeleteCommand::new().execute()
+            Commands::List => ListCommand::new().with_arguments(args).execute(),
+            Commands::Add => AddCommand::new().with_arguments(args).execute(),
+            Commands::Delete => DeleteCommand::new().with_arguments(args).execute(),
+            Commands::Done => DoneCommand::new().with_arguments(args).execute(),
+            Commands::Doctor => DoctorCommand::new().with_arguments(args).execute(),
+            Commands::Tags => TagsCommand::new().execute(),
+            Commands::Show => ShowCommand::new().with_arguments(args).execute(),
+            Commands::Next => NextCommand::new().with_arguments(args).execute(),
+            Commands::Depend => DependCommand::new().with_arguments(args).execute(),
+            Commands::Edit => EditCommand::new().with_arguments(args).execute(),
+            Commands::Group => GroupCommand::new().with_arguments(args).execute(),
+            Commands::Move => MoveCommand::new().with_arguments(args).execute(),
+            Commands::Tui => TuiCommand::new().execute(),
+            Commands::Board => BoardCommand::new().with_arguments(args).execute(),
+            Commands::Search => SearchCommand::new().with_arguments(args).execute(),
         }
     }
 }
@@ -3,4 +3,16 @@ pub enum Commands {
     List,
     Add,
     Delete,
+    Done,
+    Doctor,
+    Tags,
+    Show,
+    Next,
+    Depend,
+    Edit,
+    Group,
+    Move,
+    Tui,
+    Board,
+    Search,
 }
@@ -0,0 +1,152 @@
+use crate::error::{AppError, AppResult};
+use std::collections::HashMap;
+
+/// Defines the parsed arguments that are passed to a command
+/// after the command name itself. Positional values are kept in
+/// order, flags are stored by their name without the leading dashes
+#[derive(Default, Clone)]
+pub struct Arguments {
+    positional: Vec<String>,
+    flags: HashMap<String, Option<String>>,
+}
+
+impl Arguments {
+    /// Parses the raw arguments into positional values and flags.
+    /// Flags can be passed as `--name value` or `--name=value`.
+    /// All flags listed in `switches` never take a value.
+    pub fn parse(raw: Vec<String>, switches: &[&str]) -> Arguments {
+        let mut arguments = Arguments::default();
+        let mut iter = raw.into_iter().peekable();
+        while let Some(arg) = iter.next() {
+            match arg.strip_prefix("--") {
+                Some(flag) if !flag.is_empty() => {
+                    if let Some((name, value)) = flag.split_once('=') {
+                        arguments
+                            .flags
+                            .insert(name.to_string(), Some(value.to_string()));
+                        continue;
+                    }
+                    let mut value = None;
+                    if !switches.contains(&flag) {
+                        if let Some(next) = iter.peek() {
+                            if !next.starts_with("--") {
+                                value = iter.next();
+                            }
+                        }
+                    }
+                    arguments.flags.insert(flag.to_string(), value);
+                }
+                _ => arguments.positional.push(arg),
+            }
+        }
+        arguments
+    }
+
+    /// Gets all positional arguments in the order they have been passed
+    pub fn positional(&self) -> &[String] {
+        &self.positional
+    }
+
+    /// Checks if the flag with the given name has been passed
+    pub fn has_flag(&self, name: &str) -> bool {
+        self.flags.contains_key(name)
+    }
+
+    /// Gets the value of the flag with the given name.
+    /// Flags without a value return None
+    pub fn flag_value(&self, name: &str) -> Option<String> {
+        self.flags.get(name).cloned().flatten()
+    }
+
+    /// Parses the comma separated value of the flag as task IDs.
+    /// Returns an empty list if the flag has not been passed
+    pub fn id_list(&self, name: &str) -> AppResult<Vec<u64>> {
+        match self.flag_value(name) {
+            Some(value) => parse_id_list(&value),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Parses all positional arguments as task IDs.
+    /// The first value that is not a valid ID is returned as error
+    pub fn ids(&self) -> AppResult<Vec<u64>> {
+        self.positional
+            .iter()
+            .map(|value| {
+                value
+                    .parse::<u64>()
+                    .map_err(|_| AppError::Validation(format!("{} is not a valid task ID", value)))
+            })
+            .collect()
+    }
+}
+
+/// Parses a comma separated list of task IDs.
+/// The first value that is not a valid ID is returned as error
+pub fn parse_id_list(value: &str) -> AppResult<Vec<u64>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| {
+            id.parse::<u64>()
+                .map_err(|_| AppError::Validation(format!("{} is not a valid task ID", id)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(raw: &[&str], switches: &[&str]) -> Arguments {
+        Arguments::parse(raw.iter().map(|arg| arg.to_string()).collect(), switches)
+    }
+
+    #[test]
+    fn splits_positional_values_and_flags() {
+        let arguments = parse(&["buy", "--group", "home", "milk"], &[]);
+        assert_eq!(arguments.positional(), ["buy", "milk"]);
+        assert_eq!(arguments.flag_value("group").as_deref(), Some("home"));
+    }
+
+    #[test]
+    fn parses_flags_with_equals_sign() {
+        let arguments = parse(&["--due=+3d", "--tags=a=b"], &[]);
+        assert_eq!(arguments.flag_value("due").as_deref(), Some("+3d"));
+        assert_eq!(arguments.flag_value("tags").as_deref(), Some("a=b"));
+    }
+
+    #[test]
+    fn switches_never_take_a_value() {
+        let arguments = parse(&["--all", "5"], &["all"]);
+        assert!(arguments.has_flag("all"));
+        assert_eq!(arguments.flag_value("all"), None);
+        assert_eq!(arguments.positional(), ["5"]);
+    }
+
+    #[test]
+    fn flags_before_other_flags_have_no_value() {
+        let arguments = parse(&["--group", "--all"], &[]);
+        assert!(arguments.has_flag("group"));
+        assert_eq!(arguments.flag_value("group"), None);
+        assert!(arguments.has_flag("all"));
+    }
+
+    #[test]
+    fn keeps_a_lone_double_dash_positional() {
+        assert_eq!(parse(&["--"], &[]).positional(), ["--"]);
+    }
+
+    #[test]
+    fn parses_ids() {
+        let arguments = parse(&["1", "2", "--depends-on", "3, 4,"], &[]);
+        assert_eq!(arguments.ids().unwrap(), [1, 2]);
+        assert_eq!(arguments.id_list("depends-on").unwrap(), [3, 4]);
+        assert_eq!(arguments.id_list("missing").unwrap(), Vec::<u64>::new());
+        assert!(matches!(
+            parse(&["x"], &[]).ids(),
+            Err(AppError::Validation(_))
+        ));
+    }
+}
@@ -0,0 +1,244 @@
+use crate::error::{AppError, AppResult};
+use chrono::{Datelike, Days, Local, Months, NaiveDate, Weekday};
+use dialoguer::Input;
+
+/// Gets the current date of the local timezone
+pub fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+/// Parses the name of a weekday. Short and long
+/// english names are supported
+pub fn parse_weekday(input: &str) -> Option<Weekday> {
+    match input {
+        "mon" | "monday" => Some(Weekday::Mon),
+        "tue" | "tuesday" => Some(Weekday::Tue),
+        "wed" | "wednesday" => Some(Weekday::Wed),
+        "thu" | "thursday" => Some(Weekday::Thu),
+        "fri" | "friday" => Some(Weekday::Fri),
+        "sat" | "saturday" => Some(Weekday::Sat),
+        "sun" | "sunday" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// Parses relative input like +3d, +2w or +1m. Input without
+/// the leading plus is no offset and returns None
+fn parse_offset(input: &str, today: NaiveDate) -> AppResult<Option<NaiveDate>> {
+    let offset = match input.strip_prefix('+') {
+        Some(offset) => offset,
+        None => return Ok(None),
+    };
+    let invalid = || {
+        AppError::Validation(format!(
+            "{} is not a valid offset. Use e.g. +3d, +2w or +1m",
+            input
+        ))
+    };
+    let unit = offset.chars().last().ok_or_else(invalid)?;
+    if !matches!(unit, 'd' | 'w' | 'm') {
+        return Err(AppError::Validation(format!(
+            "{} is not a valid unit of the offset {}. Use d, w or m",
+            unit, input
+        )));
+    }
+    let amount = offset
+        .strip_suffix(unit)
+        .and_then(|amount| amount.parse::<u32>().ok())
+        .ok_or_else(invalid)?;
+    let date = match unit {
+        'd' => today.checked_add_days(Days::new(amount as u64)),
+        'w' => today.checked_add_days(Days::new(amount as u64 * 7)),
+        _ => today.checked_add_months(Months::new(amount)),
+    };
+    date.map(Some).ok_or_else(invalid)
+}
+
+/// Parses the due date input of the user. Absolute dates (YYYY-MM-DD),
+/// today, tomorrow, weekday names for the next occurrence of the
+/// weekday and offsets like +3d, +2w or +1m are supported
+pub fn parse_due_date(input: &str, today: NaiveDate) -> AppResult<NaiveDate> {
+    let value = input.trim().to_lowercase();
+    let parsed = match value.as_str() {
+        "today" => Some(today),
+        "tomorrow" => today.succ_opt(),
+        _ => match parse_weekday(&value) {
+            Some(weekday) => {
+                let days = (7 + weekday.num_days_from_monday()
+                    - today.weekday().num_days_from_monday()
+                    - 1)
+                    % 7
+                    + 1;
+                today.checked_add_days(Days::new(days as u64))
+            }
+            None => match parse_offset(&value, today)? {
+                Some(date) => Some(date),
+                None => NaiveDate::parse_from_str(&value, "%Y-%m-%d").ok(),
+            },
+        },
+    };
+    parsed.ok_or_else(|| AppError::Validation(format!("{} is not a valid due date", input)))
+}
+
+/// Parses the start of a time range. Durations like 7d, 2w or 1m
+/// go back from today, absolute dates (YYYY-MM-DD) are used as they are
+pub fn parse_since(input: &str, today: NaiveDate) -> AppResult<NaiveDate> {
+    let value = input.trim().to_lowercase();
+    let invalid = || {
+        AppError::Validation(format!(
+            "{} is not a valid time range. Use e.g. 7d, 2w, 1m or a date",
+            input
+        ))
+    };
+    let unit = value.chars().last().ok_or_else(invalid)?;
+    let amount = value.strip_suffix(unit).unwrap_or_default();
+    if amount.is_empty() || !amount.chars().all(|c| c.is_ascii_digit()) {
+        return NaiveDate::parse_from_str(&value, "%Y-%m-%d").map_err(|_| invalid());
+    }
+    let amount = amount.parse::<u32>().map_err(|_| invalid())?;
+    let date = match unit {
+        'd' => today.checked_sub_days(Days::new(amount as u64)),
+        'w' => today.checked_sub_days(Days::new(amount as u64 * 7)),
+        'm' => today.checked_sub_months(Months::new(amount)),
+        _ => {
+            return Err(AppError::Validation(format!(
+                "{} is not a valid unit of the time range {}. Use d, w or m",
+                unit, input
+            )))
+        }
+    };
+    date.ok_or_else(invalid)
+}
+
+/// Opens a prompt for the optional due date of a task, starting with the
+/// current one. The prompt is opened again until the input is valid or empty
+pub fn prompt_due_date(current: Option<NaiveDate>) -> AppResult<Option<NaiveDate>> {
+    loop {
+        let input: String = Input::new()
+            .with_prompt("Due date (e.g. 2026-12-24, tomorrow, fri, +3d; empty for none)")
+            .with_initial_text(current.map(|due| due.to_string()).unwrap_or_default())
+            .allow_empty(true)
+            .interact_text()?;
+        if input.trim().is_empty() {
+            return Ok(None);
+        }
+        match parse_due_date(&input, today()) {
+            Ok(date) => return Ok(Some(date)),
+            Err(e) => eprintln!("ERROR: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn sunday() -> NaiveDate {
+        date(2026, 10, 18)
+    }
+
+    #[test]
+    fn parses_iso_dates() {
+        assert_eq!(
+            parse_due_date("2026-12-24", sunday()).unwrap(),
+            date(2026, 12, 24)
+        );
+        assert!(parse_due_date("2026-02-30", sunday()).is_err());
+    }
+
+    #[test]
+    fn parses_relative_days() {
+        assert_eq!(parse_due_date("today", sunday()).unwrap(), sunday());
+        assert_eq!(
+            parse_due_date(" Tomorrow ", sunday()).unwrap(),
+            date(2026, 10, 19)
+        );
+    }
+
+    #[test]
+    fn parses_offsets() {
+        assert_eq!(parse_due_date("+3d", sunday()).unwrap(), date(2026, 10, 21));
+        assert_eq!(parse_due_date("+2w", sunday()).unwrap(), date(2026, 11, 1));
+        assert_eq!(parse_due_date("+1m", sunday()).unwrap(), date(2026, 11, 18));
+    }
+
+    #[test]
+    fn parses_weekdays_as_next_occurrence() {
+        assert_eq!(parse_due_date("fri", sunday()).unwrap(), date(2026, 10, 23));
+        assert_eq!(
+            parse_due_date("Monday", sunday()).unwrap(),
+            date(2026, 10, 19)
+        );
+        assert_eq!(parse_due_date("sun", sunday()).unwrap(), date(2026, 10, 25));
+    }
+
+    #[test]
+    fn refuses_empty_input() {
+        assert!(matches!(
+            parse_due_date("", sunday()),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            parse_due_date("+", sunday()),
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn refuses_invalid_offsets() {
+        for input in ["+3x", "+d", "+-1d", "+99999999999d"] {
+            assert!(
+                matches!(
+                    parse_due_date(input, sunday()),
+                    Err(AppError::Validation(_))
+                ),
+                "{} is accepted",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn refuses_non_ascii_input() {
+        for input in ["+é", "+3é", "é", "fr€"] {
+            assert!(
+                matches!(
+                    parse_due_date(input, sunday()),
+                    Err(AppError::Validation(_))
+                ),
+                "{} is accepted",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn parses_since_durations() {
+        assert_eq!(parse_since("7d", sunday()).unwrap(), date(2026, 10, 11));
+        assert_eq!(parse_since("2W", sunday()).unwrap(), date(2026, 10, 4));
+        assert_eq!(parse_since("1m", sunday()).unwrap(), date(2026, 9, 18));
+    }
+
+    #[test]
+    fn parses_since_dates() {
+        assert_eq!(
+            parse_since("2026-10-01", sunday()).unwrap(),
+            date(2026, 10, 1)
+        );
+    }
+
+    #[test]
+    fn refuses_invalid_since_input() {
+        for input in ["", "d", "7x", "7", "-7d", "99999999999d", "ü", "7ü", "€d"] {
+            assert!(
+                matches!(parse_since(input, sunday()), Err(AppError::Validation(_))),
+                "{} is accepted",
+                input
+            );
+        }
+    }
+}
@@ -1,17 +1,28 @@
-use std::error::Error;
-use std::io::Result;
+use crate::error::{AppError, AppResult};
+use std::io::{ErrorKind, Result};
 
 /// Defines the base methods that must be implemented
 /// if input errors will be handled
 pub trait InputErrorHandling {
-    /// Handles the input error
-    fn handle_select_error(&mut self, input: std::io::Result<Option<usize>>) -> Option<usize> {
+    /// Handles the input error. Closing the prompt without a selection
+    /// cancels the command, a missing terminal is a validation error
+    fn handle_select_error(&mut self, input: Result<Option<usize>>) -> AppResult<usize> {
         match input {
-            Err(e) => {
-                println!("ERROR: {}", e.description());
-                None
-            }
-            Ok(val) => val,
+            Ok(Some(val)) => Ok(val),
+            Ok(None) => Err(AppError::Cancelled),
+            Err(e) if e.kind() == ErrorKind::NotConnected => Err(AppError::Validation(
+                "The select needs an interactive terminal".to_string(),
+            )),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Handles the result of a confirmation prompt.
+    /// Declining the prompt cancels the command
+    fn handle_confirm_error(&mut self, input: Result<bool>) -> AppResult<()> {
+        match input? {
+            true => Ok(()),
+            false => Err(AppError::Cancelled),
         }
     }
 }
@@ -1 +1,6 @@
+pub mod arguments;
+pub mod due_date;
 pub mod error_handling;
+pub mod notes;
+pub mod recurrence;
+pub mod tags;
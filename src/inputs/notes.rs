@@ -0,0 +1,46 @@
+use crate::error::AppResult;
+use dialoguer::Editor;
+use std::env;
+use std::io::{stdin, BufRead, Read};
+
+/// Normalizes the entered notes. Empty notes are stored as None
+fn normalize(notes: &str) -> Option<String> {
+    let trimmed = notes.trim_end();
+    if trimmed.trim().is_empty() {
+        return None;
+    }
+    Some(trimmed.to_string())
+}
+
+/// Opens a prompt for editing the notes of a task. If $VISUAL or $EDITOR
+/// is set, the notes are edited in that editor. Otherwise, the lines are
+/// read from the terminal until an empty line is entered
+pub fn prompt_notes(current: Option<&str>) -> AppResult<Option<String>> {
+    if env::var_os("VISUAL").is_some() || env::var_os("EDITOR").is_some() {
+        return match Editor::new().edit(current.unwrap_or(""))? {
+            Some(notes) => Ok(normalize(&notes)),
+            None => Ok(current.map(String::from)),
+        };
+    }
+    eprintln!("Notes (finish with an empty line):");
+    let mut lines = vec![];
+    for line in stdin().lock().lines() {
+        let line = line?;
+        if line.is_empty() {
+            break;
+        }
+        lines.push(line);
+    }
+    Ok(normalize(&lines.join("\n")))
+}
+
+/// Gets the notes passed as argument. If the value
+/// is -, the notes are read from the standard input
+pub fn read_notes_argument(value: &str) -> AppResult<Option<String>> {
+    if value != "-" {
+        return Ok(normalize(value));
+    }
+    let mut notes = String::new();
+    stdin().read_to_string(&mut notes)?;
+    Ok(normalize(&notes))
+}
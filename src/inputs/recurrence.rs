@@ -0,0 +1,26 @@
+use crate::error::AppResult;
+use crate::storage_handler::recurrence::Recurrence;
+use dialoguer::Input;
+
+/// Opens a prompt for the optional recurrence of a task, starting with the
+/// current one. The prompt is opened again until the input is valid or empty
+pub fn prompt_recurrence(current: Option<&Recurrence>) -> AppResult<Option<Recurrence>> {
+    loop {
+        let input: String = Input::new()
+            .with_prompt("Repeat (daily, weekly:mon,fri, monthly:15, every:3d; empty for none)")
+            .with_initial_text(
+                current
+                    .map(|recurrence| recurrence.to_string())
+                    .unwrap_or_default(),
+            )
+            .allow_empty(true)
+            .interact_text()?;
+        if input.trim().is_empty() {
+            return Ok(None);
+        }
+        match Recurrence::parse(&input) {
+            Ok(recurrence) => return Ok(Some(recurrence)),
+            Err(e) => eprintln!("ERROR: {}", e),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use crate::error::{AppError, AppResult};
+use crate::storage_handler::Task;
+
+/// Splits the words of the input that start with a + from the
+/// other words. Returns the remaining text and the tags without the +
+pub fn extract_tags(input: &str) -> (String, Vec<String>) {
+    let mut words = vec![];
+    let mut tags: Vec<String> = vec![];
+    for word in input.split_whitespace() {
+        match word.strip_prefix('+') {
+            Some(tag) if !tag.is_empty() => {
+                if !tags.iter().any(|existing| existing == tag) {
+                    tags.push(tag.to_string());
+                }
+            }
+            _ => words.push(word),
+        }
+    }
+    (words.join(" "), tags)
+}
+
+/// Defines a filter of tasks by their tags. A task matches if it has
+/// all included tags and none of the excluded tags
+#[derive(Default)]
+pub struct TagFilter {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl TagFilter {
+    /// Parses the filter from values like +backend or -blocked
+    pub fn parse(values: &[String]) -> AppResult<TagFilter> {
+        let mut filter = TagFilter::default();
+        for value in values {
+            if let Some(tag) = value.strip_prefix('+').filter(|tag| !tag.is_empty()) {
+                filter.include.push(tag.to_string());
+            } else if let Some(tag) = value.strip_prefix('-').filter(|tag| !tag.is_empty()) {
+                filter.exclude.push(tag.to_string());
+            } else {
+                return Err(AppError::Validation(format!(
+                    "{} is not a valid tag filter. Use +tag or -tag",
+                    value
+                )));
+            }
+        }
+        Ok(filter)
+    }
+
+    /// Checks if the task matches the filter
+    pub fn matches(&self, task: &Task) -> bool {
+        self.include.iter().all(|tag| task.tags.contains(tag))
+            && !self.exclude.iter().any(|tag| task.tags.contains(tag))
+    }
+}
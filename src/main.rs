@@ -1,16 +1,25 @@
 use crate::commands::command_trait::Command;
+use crate::commands::doctor_command::DoctorCommand;
 use crate::handler::command_handler::CommandHandler;
+use std::process;
 
 mod commands;
+mod error;
 mod handler;
 mod inputs;
 mod storage_handler;
+mod tui;
 
 fn main() {
     let mut command_handler = CommandHandler::new(std::env::args());
     let command = command_handler.get_command();
-    match command {
+    let result = match command {
         Some(cmd) => command_handler.execute_by_enum(cmd),
         None => commands::help_command::HelpCommand::new().execute(),
+    };
+    let result = result.or_else(|error| DoctorCommand::new().recover_from(error));
+    if let Err(error) = result {
+        eprintln!("ERROR: {}", error);
+        process::exit(error.exit_code());
     }
 }
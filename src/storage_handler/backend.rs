@@ -0,0 +1,66 @@
+use crate::error::AppResult;
+use crate::storage_handler::Task;
+use std::collections::HashMap;
+
+/// Defines all operations a storage backend needs to
+/// implement to be used by the storage handler
+pub trait StorageBackend {
+    /// Gets all existing tasks
+    fn get_all_tasks(&mut self) -> AppResult<Vec<Task>>;
+
+    /// Gets the names of all existing task groups
+    fn get_all_groups(&mut self) -> AppResult<Vec<String>>;
+
+    /// Adds the task with the next free ID and returns the ID
+    fn add_single_task(&mut self, task: Task) -> AppResult<u64>;
+
+    /// Adds a new task group
+    fn add_task_group(&mut self, title: String) -> AppResult<()>;
+
+    /// Saves the given tasks. Existing tasks only get the fields that differ
+    /// from their original version, all other tasks are replaced. Tasks with
+    /// an original version that are not stored anymore are skipped
+    fn write_task_data(&mut self, data: Vec<Task>, originals: &[Task]) -> AppResult<()>;
+
+    /// Removes all tasks with the given IDs
+    fn delete_tasks(&mut self, ids: &[u64]) -> AppResult<()>;
+
+    /// Renames the task group. The tasks of the group are not changed
+    fn rename_task_group(&mut self, old: &str, new: &str) -> AppResult<()>;
+
+    /// Removes the task group. The tasks of the group are not changed
+    fn delete_task_group(&mut self, name: &str) -> AppResult<()>;
+
+    /// Drops all cached data, so that the next read
+    /// sees the changes of other instances
+    fn reload(&mut self) {}
+
+    /// Gets a single task by its ID
+    fn get_task_by_id(&mut self, id: u64) -> AppResult<Option<Task>> {
+        Ok(self.get_all_tasks()?.into_iter().find(|task| task.id == id))
+    }
+
+    /// Gets all tasks of the given group. If no group
+    /// is given, all tasks without a group are returned
+    fn get_tasks_by_group(&mut self, group: Option<&str>) -> AppResult<Vec<Task>> {
+        Ok(self
+            .get_all_tasks()?
+            .into_iter()
+            .filter(|task| task.group.as_deref() == group)
+            .collect())
+    }
+
+    /// Gets all tags that are used by tasks with the number of
+    /// tasks using them. The most used tags are listed first
+    fn get_tag_counts(&mut self) -> AppResult<Vec<(String, u64)>> {
+        let mut counts: HashMap<String, u64> = HashMap::new();
+        for task in self.get_all_tasks()? {
+            for tag in task.tags {
+                *counts.entry(tag).or_insert(0) += 1;
+            }
+        }
+        let mut counts = counts.into_iter().collect::<Vec<(String, u64)>>();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(counts)
+    }
+}
@@ -0,0 +1,196 @@
+use crate::error::AppResult;
+use crate::storage_handler::backend::StorageBackend;
+use crate::storage_handler::lock::DataLock;
+use crate::storage_handler::{get_data_dir, DataFile, Task};
+use std::fs;
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+
+/// Storage backend that keeps all data in
+/// the data.json file of the app data directory
+pub struct JsonBackend {
+    root_dir: Option<PathBuf>,
+    data: Option<DataFile>,
+}
+
+impl JsonBackend {
+    /// Creates a new instance of the json backend. The data
+    /// directory is determined and the file read on the first access
+    pub fn new() -> Self {
+        JsonBackend {
+            root_dir: None,
+            data: None,
+        }
+    }
+
+    /// Gets the root data directory. It is
+    /// determined only once on the first access
+    fn get_root_dir(&mut self) -> AppResult<PathBuf> {
+        if self.root_dir.is_none() {
+            self.root_dir = Some(get_data_dir()?);
+        }
+        Ok(self.root_dir.clone().unwrap())
+    }
+
+    /// Reads the raw string from the data.json file.
+    /// If the file does not exist yet, the default data is returned.
+    /// The file itself is created on the first write
+    pub fn read_tasks_file(&mut self) -> AppResult<String> {
+        let initial_data = r#"{"tasks": [], "groups": []}"#;
+        let data = match fs::read_to_string(self.get_file_path("")?) {
+            Ok(data) => data,
+            Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        if data.is_empty() {
+            return Ok(String::from(initial_data));
+        }
+        Ok(data)
+    }
+
+    /// Gets the parsed data of the data.json file
+    /// as the DataFile struct. Older layouts are migrated to the
+    /// current version. The file is parsed only once and kept in memory afterwards
+    fn get_data(&mut self) -> AppResult<&mut DataFile> {
+        if self.data.is_none() {
+            let read_data = self.read_tasks_file()?;
+            self.data = Some(DataFile::from_json(read_data.as_str())?);
+        }
+        Ok(self.data.as_mut().unwrap())
+    }
+
+    /// Runs a whole read-modify-write cycle on the data while
+    /// holding the lock of the data directory. The data is re-read
+    /// after the lock has been acquired, so that changes of other
+    /// instances are not overwritten
+    fn modify_data<F: FnOnce(&mut DataFile)>(&mut self, modify: F) -> AppResult<()> {
+        let root_dir = self.ensure_root_dir()?;
+        let _lock = DataLock::acquire(&root_dir)?;
+        self.data = None;
+        modify(self.get_data()?);
+        self.write_data()
+    }
+
+    /// Replaces the whole content of the data.json file
+    /// with the given data without reading it before
+    pub fn replace_data(&mut self, data: DataFile) -> AppResult<()> {
+        let root_dir = self.ensure_root_dir()?;
+        let _lock = DataLock::acquire(&root_dir)?;
+        self.data = Some(data);
+        self.write_data()
+    }
+
+    /// Creates the root data directory if it does not exist
+    /// and returns its path
+    fn ensure_root_dir(&mut self) -> AppResult<PathBuf> {
+        let root_dir = self.get_root_dir()?;
+        if !root_dir.exists() {
+            fs::create_dir_all(&root_dir)?;
+        }
+        Ok(root_dir)
+    }
+
+    /// Gets the path of the data.json file with the
+    /// given suffix appended to the file name
+    fn get_file_path(&mut self, suffix: &str) -> AppResult<PathBuf> {
+        Ok(self
+            .get_root_dir()?
+            .join(String::from("data.json") + suffix))
+    }
+
+    /// Writes the cached data as string into the data.json file.
+    /// The data is written into a temporary file first, that is
+    /// renamed over the original one. The previous version is kept
+    /// as data.json.bak
+    fn write_data(&mut self) -> AppResult<()> {
+        let raw = serde_json::to_string(self.get_data()?)?;
+        let path = self.get_file_path("")?;
+        let temp_path = self.get_file_path(".tmp")?;
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&temp_path)?;
+        file.write_all(raw.as_str().as_ref())?;
+        file.sync_all()?;
+        if path.exists() {
+            fs::copy(&path, self.get_file_path(".bak")?)?;
+        }
+        fs::rename(&temp_path, &path)?;
+        if let Ok(dir) = File::open(self.get_root_dir()?) {
+            dir.sync_all().ok();
+        }
+        Ok(())
+    }
+}
+
+impl StorageBackend for JsonBackend {
+    fn get_all_tasks(&mut self) -> AppResult<Vec<Task>> {
+        Ok(self.get_data()?.tasks.clone())
+    }
+
+    fn get_all_groups(&mut self) -> AppResult<Vec<String>> {
+        Ok(self.get_data()?.groups.clone())
+    }
+
+    fn add_single_task(&mut self, task: Task) -> AppResult<u64> {
+        let mut id = 0;
+        self.modify_data(|data| id = data.add_task(task))?;
+        Ok(id)
+    }
+
+    fn add_task_group(&mut self, title: String) -> AppResult<()> {
+        self.modify_data(|data| data.groups.push(title))
+    }
+
+    fn write_task_data(&mut self, data: Vec<Task>, originals: &[Task]) -> AppResult<()> {
+        self.modify_data(|file_data| file_data.merge_tasks(data, originals))
+    }
+
+    fn delete_tasks(&mut self, ids: &[u64]) -> AppResult<()> {
+        self.modify_data(|data| data.remove_tasks(ids))
+    }
+
+    fn rename_task_group(&mut self, old: &str, new: &str) -> AppResult<()> {
+        self.modify_data(|data| data.rename_group(old, new))
+    }
+
+    fn delete_task_group(&mut self, name: &str) -> AppResult<()> {
+        self.modify_data(|data| data.remove_group(name))
+    }
+
+    fn reload(&mut self) {
+        self.data = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage_handler::StorageHandler;
+    use std::process;
+
+    #[test]
+    fn writes_do_not_restore_tasks_deleted_by_another_instance() {
+        let root_dir = std::env::temp_dir().join(format!("terminal-todo-json-{}", process::id()));
+        let open = || {
+            StorageHandler::with_backend(Box::new(JsonBackend {
+                root_dir: Some(root_dir.clone()),
+                data: None,
+            }))
+        };
+        let mut first = open();
+        let id = first
+            .add_single_task(Task::new("a".to_string(), None))
+            .unwrap();
+        let mut second = open();
+        let mut task = second.get_task_by_id(id).unwrap().unwrap();
+        first.delete_tasks(&[id]).unwrap();
+        task.title = "renamed".to_string();
+        second.write_task_data(vec![task]).unwrap();
+        let tasks = open().get_all_tasks().unwrap();
+        fs::remove_dir_all(&root_dir).unwrap();
+        assert!(tasks.is_empty());
+    }
+}
@@ -0,0 +1,69 @@
+use crate::error::{AppError, AppResult};
+use std::env;
+use std::fs::{File, OpenOptions, TryLockError};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The default number of seconds to wait for another
+/// instance that holds the lock
+const DEFAULT_LOCK_TIMEOUT: u64 = 10;
+
+/// Set after the first waiting message, so that it
+/// is printed only once per process
+static NOTIFIED: AtomicBool = AtomicBool::new(false);
+
+/// Exclusive advisory lock on the data directory. The lock
+/// is released as soon as the instance is dropped
+pub struct DataLock {
+    file: File,
+}
+
+impl DataLock {
+    /// Gets the timeout for acquiring the lock. It can be changed
+    /// with the TERMINAL_TODO_LOCK_TIMEOUT environment variable in seconds
+    pub fn get_timeout() -> Duration {
+        let seconds = env::var("TERMINAL_TODO_LOCK_TIMEOUT")
+            .ok()
+            .and_then(|value| value.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_LOCK_TIMEOUT);
+        Duration::from_secs(seconds)
+    }
+
+    /// Acquires the lock on the given data directory. If another instance
+    /// holds the lock, this waits until the timeout is reached
+    pub fn acquire(root_dir: &Path) -> AppResult<DataLock> {
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(root_dir.join("data.lock"))?;
+        let timeout = DataLock::get_timeout();
+        let start = Instant::now();
+        loop {
+            match file.try_lock() {
+                Ok(()) => return Ok(DataLock { file }),
+                Err(TryLockError::WouldBlock) => {
+                    if start.elapsed() >= timeout {
+                        return Err(AppError::Locked(format!(
+                            "Another instance is writing. Gave up after {} seconds",
+                            timeout.as_secs()
+                        )));
+                    }
+                    if !NOTIFIED.swap(true, Ordering::Relaxed) {
+                        eprintln!("Another instance is writing, waiting...");
+                    }
+                    thread::sleep(Duration::from_millis(50));
+                }
+                Err(TryLockError::Error(e)) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+impl Drop for DataLock {
+    fn drop(&mut self) {
+        self.file.unlock().ok();
+    }
+}
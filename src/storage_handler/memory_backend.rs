@@ -0,0 +1,56 @@
+use crate::error::AppResult;
+use crate::storage_handler::backend::StorageBackend;
+use crate::storage_handler::{DataFile, Task};
+
+/// Storage backend that only keeps the data in memory.
+/// Nothing is persisted, so it is only used by the tests
+#[derive(Default)]
+pub struct MemoryBackend {
+    data: DataFile,
+}
+
+impl MemoryBackend {
+    /// Creates a new empty in-memory backend
+    pub fn new() -> Self {
+        MemoryBackend::default()
+    }
+}
+
+impl StorageBackend for MemoryBackend {
+    fn get_all_tasks(&mut self) -> AppResult<Vec<Task>> {
+        Ok(self.data.tasks.clone())
+    }
+
+    fn get_all_groups(&mut self) -> AppResult<Vec<String>> {
+        Ok(self.data.groups.clone())
+    }
+
+    fn add_single_task(&mut self, task: Task) -> AppResult<u64> {
+        Ok(self.data.add_task(task))
+    }
+
+    fn add_task_group(&mut self, title: String) -> AppResult<()> {
+        self.data.groups.push(title);
+        Ok(())
+    }
+
+    fn write_task_data(&mut self, data: Vec<Task>, originals: &[Task]) -> AppResult<()> {
+        self.data.merge_tasks(data, originals);
+        Ok(())
+    }
+
+    fn delete_tasks(&mut self, ids: &[u64]) -> AppResult<()> {
+        self.data.remove_tasks(ids);
+        Ok(())
+    }
+
+    fn rename_task_group(&mut self, old: &str, new: &str) -> AppResult<()> {
+        self.data.rename_group(old, new);
+        Ok(())
+    }
+
+    fn delete_task_group(&mut self, name: &str) -> AppResult<()> {
+        self.data.remove_group(name);
+        Ok(())
+    }
+}
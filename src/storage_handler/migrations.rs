@@ -0,0 +1,229 @@
+use crate::error::{AppError, AppResult};
+use serde_json::{json, Value};
+
+/// The version of the data file layout that
+/// is written by this version of the cli
+pub const CURRENT_VERSION: u64 = 11;
+
+/// A migration upgrades the raw data by exactly one version
+type Migration = fn(&mut Value);
+
+/// All migrations ordered by the version they upgrade from.
+/// The migration at index 0 upgrades version 0 to version 1
+const MIGRATIONS: [Migration; CURRENT_VERSION as usize] = [
+    migrate_v0_to_v1,
+    migrate_v1_to_v2,
+    migrate_v2_to_v3,
+    migrate_v3_to_v4,
+    migrate_v4_to_v5,
+    migrate_v5_to_v6,
+    migrate_v6_to_v7,
+    migrate_v7_to_v8,
+    migrate_v8_to_v9,
+    migrate_v9_to_v10,
+    migrate_v10_to_v11,
+];
+
+/// Gets the version of the raw data. Files without
+/// a version key have been written before versioning existed
+fn get_version(data: &Value) -> u64 {
+    data.get("version").and_then(Value::as_u64).unwrap_or(0)
+}
+
+/// Upgrades the raw data to the current version by running all
+/// required migrations in order. Data written by a newer version
+/// of the cli is refused, because it might contain unknown fields
+pub fn migrate(mut data: Value) -> AppResult<Value> {
+    let version = get_version(&data);
+    if version > CURRENT_VERSION {
+        return Err(AppError::Validation(format!(
+            "The data file has version {}, but this version of the cli only supports version {}. Please update terminal-todo",
+            version, CURRENT_VERSION
+        )));
+    }
+    for migration in MIGRATIONS.iter().skip(version as usize) {
+        migration(&mut data);
+    }
+    Ok(data)
+}
+
+/// Sets the field with the given value on all tasks
+fn add_task_field(data: &mut Value, name: &str, value: Value) {
+    if let Some(tasks) = data["tasks"].as_array_mut() {
+        for task in tasks.iter_mut() {
+            task[name] = value.clone();
+        }
+    }
+}
+
+/// Adds the version key to files that have
+/// been written before versioning existed
+fn migrate_v0_to_v1(data: &mut Value) {
+    data["version"] = json!(1);
+}
+
+/// Adds the persistent ID counter. It starts
+/// after the highest ID that is currently in use
+fn migrate_v1_to_v2(data: &mut Value) {
+    let next_id = data["tasks"]
+        .as_array()
+        .map(|tasks| {
+            tasks
+                .iter()
+                .filter_map(|task| task["id"].as_u64())
+                .map(|id| id + 1)
+                .max()
+                .unwrap_or(0)
+        })
+        .unwrap_or(0);
+    data["next_id"] = json!(next_id);
+    data["version"] = json!(2);
+}
+
+/// Adds the optional due date to all tasks
+fn migrate_v2_to_v3(data: &mut Value) {
+    add_task_field(data, "due", Value::Null);
+    data["version"] = json!(3);
+}
+
+/// Adds the priority to all tasks
+fn migrate_v3_to_v4(data: &mut Value) {
+    add_task_field(data, "priority", json!("none"));
+    data["version"] = json!(4);
+}
+
+/// Adds the empty tag list to all tasks
+fn migrate_v4_to_v5(data: &mut Value) {
+    add_task_field(data, "tags", json!([]));
+    data["version"] = json!(5);
+}
+
+/// Adds the optional notes to all tasks
+fn migrate_v5_to_v6(data: &mut Value) {
+    add_task_field(data, "notes", Value::Null);
+    data["version"] = json!(6);
+}
+
+/// Adds the optional parent task to all tasks
+fn migrate_v6_to_v7(data: &mut Value) {
+    add_task_field(data, "parent", Value::Null);
+    data["version"] = json!(7);
+}
+
+/// Adds the empty list of dependencies to all tasks
+fn migrate_v7_to_v8(data: &mut Value) {
+    add_task_field(data, "depends_on", json!([]));
+    data["version"] = json!(8);
+}
+
+/// Adds the optional recurrence rule to all tasks
+fn migrate_v8_to_v9(data: &mut Value) {
+    add_task_field(data, "recurrence", Value::Null);
+    data["version"] = json!(9);
+}
+
+/// Replaces the finished flag of a single task with
+/// the status. Finished tasks become done
+pub fn migrate_finished_status(task: &mut Value) {
+    if let Some(object) = task.as_object_mut() {
+        if let Some(finished) = object.remove("finished") {
+            let status = match finished.as_bool() {
+                Some(true) => "done",
+                _ => "todo",
+            };
+            object.insert("status".to_string(), json!(status));
+        }
+    }
+}
+
+/// Replaces the finished flag of all tasks with the status
+fn migrate_v9_to_v10(data: &mut Value) {
+    if let Some(tasks) = data["tasks"].as_array_mut() {
+        for task in tasks.iter_mut() {
+            migrate_finished_status(task);
+        }
+    }
+    data["version"] = json!(10);
+}
+
+/// Adds the unknown created, updated and completed times to all tasks
+fn migrate_v10_to_v11(data: &mut Value) {
+    add_task_field(data, "created_at", Value::Null);
+    add_task_field(data, "updated_at", Value::Null);
+    add_task_field(data, "completed_at", Value::Null);
+    data["version"] = json!(11);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_unversioned_data_to_current_version() {
+        let data = json!({
+            "tasks": [
+                { "id": 0, "finished": true, "title": "Done", "group": null },
+                { "id": 4, "finished": false, "title": "Open", "group": "work" }
+            ],
+            "groups": ["work"]
+        });
+        let migrated = migrate(data).unwrap();
+        assert_eq!(migrated["version"], json!(CURRENT_VERSION));
+        assert_eq!(migrated["next_id"], json!(5));
+        let task = &migrated["tasks"][1];
+        assert_eq!(task["status"], json!("todo"));
+        assert_eq!(task["priority"], json!("none"));
+        assert_eq!(task["tags"], json!([]));
+        assert_eq!(task["depends_on"], json!([]));
+        for field in [
+            "due",
+            "notes",
+            "parent",
+            "recurrence",
+            "created_at",
+            "updated_at",
+            "completed_at",
+        ] {
+            assert_eq!(task[field], Value::Null, "{} is not migrated", field);
+        }
+        assert!(task.get("finished").is_none());
+        assert_eq!(migrated["tasks"][0]["status"], json!("done"));
+    }
+
+    #[test]
+    fn each_migration_sets_its_version() {
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            let mut data = json!({ "version": index, "next_id": 0, "tasks": [], "groups": [] });
+            migration(&mut data);
+            assert_eq!(get_version(&data), index as u64 + 1);
+        }
+    }
+
+    #[test]
+    fn migrates_only_missing_versions() {
+        let data = json!({
+            "version": 9,
+            "next_id": 3,
+            "tasks": [{ "id": 2, "finished": true, "title": "Done", "group": null, "priority": "high" }],
+            "groups": []
+        });
+        let migrated = migrate(data).unwrap();
+        assert_eq!(migrated["next_id"], json!(3));
+        assert_eq!(migrated["tasks"][0]["priority"], json!("high"));
+        assert_eq!(migrated["tasks"][0]["status"], json!("done"));
+        assert_eq!(migrated["tasks"][0]["created_at"], Value::Null);
+    }
+
+    #[test]
+    fn keeps_current_data_unchanged() {
+        let data =
+            json!({ "version": CURRENT_VERSION, "next_id": 1, "tasks": [], "groups": ["work"] });
+        assert_eq!(migrate(data.clone()).unwrap(), data);
+    }
+
+    #[test]
+    fn refuses_newer_data() {
+        let data = json!({ "version": CURRENT_VERSION + 1, "tasks": [], "groups": [] });
+        assert!(matches!(migrate(data), Err(AppError::Validation(_))));
+    }
+}
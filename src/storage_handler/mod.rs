@@ -0,0 +1,851 @@
+use crate::error::{AppError, AppResult};
+use crate::storage_handler::backend::StorageBackend;
+use crate::storage_handler::json_backend::JsonBackend;
+use crate::storage_handler::migrations::CURRENT_VERSION;
+use crate::storage_handler::recurrence::Recurrence;
+use crate::storage_handler::sqlite_backend::SqliteBackend;
+use crate::storage_handler::status::Status;
+use chrono::{DateTime, NaiveDate, Utc};
+use platform_dirs::AppDirs;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::io;
+use std::path::PathBuf;
+
+pub mod backend;
+pub mod json_backend;
+pub mod lock;
+#[cfg(test)]
+pub mod memory_backend;
+pub mod migrations;
+pub mod recurrence;
+pub mod repair;
+pub mod sqlite_backend;
+pub mod status;
+
+/// Defines the base structure of
+/// the storage handler. All operations are
+/// delegated to the configured storage backend
+pub struct StorageHandler {
+    backend: Box<dyn StorageBackend>,
+    originals: HashMap<u64, Task>,
+}
+
+/// The priority of a task. The variants are
+/// ordered from the lowest to the highest priority
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    #[default]
+    None,
+    Low,
+    Medium,
+    High,
+    Urgent,
+}
+
+impl Priority {
+    /// All priorities ordered from the lowest to the highest
+    pub const ALL: [Priority; 5] = [
+        Priority::None,
+        Priority::Low,
+        Priority::Medium,
+        Priority::High,
+        Priority::Urgent,
+    ];
+
+    /// Gets the name of the priority that is
+    /// used for input and output
+    pub fn name(&self) -> &'static str {
+        match self {
+            Priority::None => "none",
+            Priority::Low => "low",
+            Priority::Medium => "medium",
+            Priority::High => "high",
+            Priority::Urgent => "urgent",
+        }
+    }
+
+    /// Gets the marker that is shown next to the task
+    pub fn marker(&self) -> &'static str {
+        match self {
+            Priority::None => "",
+            Priority::Low => "!",
+            Priority::Medium => "!!",
+            Priority::High => "!!!",
+            Priority::Urgent => "!!!!",
+        }
+    }
+
+    /// Parses the priority by its name
+    pub fn parse(input: &str) -> AppResult<Priority> {
+        Priority::ALL
+            .into_iter()
+            .find(|priority| priority.name() == input.trim().to_lowercase())
+            .ok_or_else(|| {
+                AppError::Validation(format!(
+                    "{} is not a valid priority. Use one of none, low, medium, high, urgent",
+                    input
+                ))
+            })
+    }
+}
+
+/// A Task type that defines how a task looks in the memory
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct Task {
+    pub id: u64,
+    pub status: Status,
+    pub title: String,
+    pub group: Option<String>,
+    #[serde(default)]
+    pub due: Option<NaiveDate>,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub parent: Option<u64>,
+    #[serde(default)]
+    pub depends_on: Vec<u64>,
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl Task {
+    /// Creates a new task with the todo status. The ID
+    /// is assigned by the storage when the task is added
+    pub fn new(title: String, group: Option<String>) -> Task {
+        Task {
+            id: 0,
+            status: Status::Todo,
+            title,
+            group,
+            due: None,
+            priority: Priority::None,
+            tags: vec![],
+            notes: None,
+            parent: None,
+            depends_on: vec![],
+            recurrence: None,
+            created_at: None,
+            updated_at: None,
+            completed_at: None,
+        }
+    }
+
+    /// Creates the next occurrence of the recurring task that
+    /// has been completed today. The new occurrence takes over the
+    /// recurrence and gets the next due date of it
+    pub fn next_occurrence(&self, recurrence: Recurrence, today: NaiveDate) -> Task {
+        Task {
+            id: 0,
+            status: Status::Todo,
+            due: Some(recurrence.next_due(self.due, today)),
+            depends_on: vec![],
+            recurrence: Some(recurrence),
+            created_at: None,
+            updated_at: None,
+            completed_at: None,
+            ..self.clone()
+        }
+    }
+
+    /// Sets the timestamps of the task that is saved at the given time.
+    /// The updated time only changes if the task differs from the stored
+    /// version, the completed time is set when the task becomes done
+    fn stamp(&mut self, stored: Option<&Task>, now: DateTime<Utc>) {
+        let stored = match stored {
+            Some(stored) => stored,
+            None => {
+                self.created_at = Some(now);
+                self.updated_at = Some(now);
+                self.completed_at = (self.status == Status::Done).then_some(now);
+                return;
+            }
+        };
+        self.created_at = stored.created_at;
+        self.updated_at = stored.updated_at;
+        self.completed_at = stored.completed_at;
+        if self == stored {
+            return;
+        }
+        self.updated_at = Some(now);
+        self.completed_at = match (self.status, stored.status) {
+            (Status::Done, Status::Done) => stored.completed_at.or(Some(now)),
+            (Status::Done, _) => Some(now),
+            _ => None,
+        };
+    }
+
+    /// Checks if the task is done or cancelled
+    pub fn is_closed(&self) -> bool {
+        self.status.is_closed()
+    }
+
+    /// Gets all tasks this task depends on that are not closed yet.
+    /// Dependencies that do not exist anymore are ignored
+    pub fn open_blockers<'a>(&self, tasks: &'a [Task]) -> Vec<&'a Task> {
+        tasks
+            .iter()
+            .filter(|task| self.depends_on.contains(&task.id) && !task.is_closed())
+            .collect()
+    }
+}
+
+/// Checks if the dependencies of the task lead back to the task itself.
+/// The stored version of the task is replaced by the given one
+pub fn has_dependency_cycle(tasks: &[Task], task: &Task) -> bool {
+    let mut visited = HashSet::new();
+    let mut pending = task.depends_on.clone();
+    while let Some(id) = pending.pop() {
+        if id == task.id {
+            return true;
+        }
+        if !visited.insert(id) {
+            continue;
+        }
+        if let Some(dependency) = tasks.iter().find(|other| other.id == id) {
+            pending.extend(dependency.depends_on.iter().copied());
+        }
+    }
+    false
+}
+
+/// The base structure of the json file that contains all
+/// the important data about the cli.
+#[derive(Serialize, Deserialize)]
+pub struct DataFile {
+    pub version: u64,
+    pub next_id: u64,
+    pub tasks: Vec<Task>,
+    pub groups: Vec<String>,
+}
+
+impl Default for DataFile {
+    fn default() -> Self {
+        DataFile {
+            version: CURRENT_VERSION,
+            next_id: 0,
+            tasks: vec![],
+            groups: vec![],
+        }
+    }
+}
+
+/// Gets the directory where all data files of the cli are stored.
+/// Fails if the home directory of the user cannot be determined
+pub fn get_data_dir() -> AppResult<PathBuf> {
+    AppDirs::new(Some("terminalToDo"), false)
+        .map(|dirs| dirs.data_dir)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "Cannot determine the data directory",
+            )
+            .into()
+        })
+}
+
+impl Task {
+    /// Applies all fields of the changed task that differ from its original
+    /// version. Fields that have been changed by another instance are kept
+    fn apply_changes(&mut self, original: &Task, changed: Task) {
+        apply_change(&mut self.status, &original.status, changed.status);
+        apply_change(&mut self.title, &original.title, changed.title);
+        apply_change(&mut self.group, &original.group, changed.group);
+        apply_change(&mut self.due, &original.due, changed.due);
+        apply_change(&mut self.priority, &original.priority, changed.priority);
+        apply_change(&mut self.tags, &original.tags, changed.tags);
+        apply_change(&mut self.notes, &original.notes, changed.notes);
+        apply_change(&mut self.parent, &original.parent, changed.parent);
+        apply_change(
+            &mut self.depends_on,
+            &original.depends_on,
+            changed.depends_on,
+        );
+        apply_change(
+            &mut self.recurrence,
+            &original.recurrence,
+            changed.recurrence,
+        );
+        apply_change(
+            &mut self.created_at,
+            &original.created_at,
+            changed.created_at,
+        );
+        apply_change(
+            &mut self.updated_at,
+            &original.updated_at,
+            changed.updated_at,
+        );
+        apply_change(
+            &mut self.completed_at,
+            &original.completed_at,
+            changed.completed_at,
+        );
+    }
+}
+
+/// Replaces the stored value if the changed value differs from the original one
+fn apply_change<T: PartialEq>(stored: &mut T, original: &T, changed: T) {
+    if changed != *original {
+        *stored = changed;
+    }
+}
+
+impl DataFile {
+    /// Parses the raw json data, upgrades it to the current version
+    /// and validates it. Data that is newer than the cli is refused
+    pub fn from_json(raw: &str) -> AppResult<DataFile> {
+        let data = DataFile::from_json_unchecked(raw)?;
+        data.validate()?;
+        Ok(data)
+    }
+
+    /// Parses the raw json data and upgrades it to the current
+    /// version without validating the task IDs
+    pub fn from_json_unchecked(raw: &str) -> AppResult<DataFile> {
+        let value: serde_json::Value = serde_json::from_str(raw)?;
+        let migrated = migrations::migrate(value)?;
+        Ok(serde_json::from_value(migrated)?)
+    }
+
+    /// Checks that all task IDs are unique and
+    /// lower than the next ID that will be allocated
+    pub fn validate(&self) -> AppResult<()> {
+        let mut seen = HashSet::new();
+        for task in self.tasks.iter() {
+            if !seen.insert(task.id) {
+                return Err(AppError::Parse(format!(
+                    "The task ID {} is used more than once",
+                    task.id
+                )));
+            }
+            if task.id >= self.next_id {
+                return Err(AppError::Parse(format!(
+                    "The task ID {} is not lower than the next ID {}",
+                    task.id, self.next_id
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the task with the next free ID and returns the ID.
+    /// IDs are never reused, even if tasks have been deleted
+    pub fn add_task(&mut self, mut task: Task) -> u64 {
+        task.id = self.next_id;
+        self.next_id += 1;
+        self.tasks.push(task);
+        self.next_id - 1
+    }
+
+    /// Merges the given tasks into the existing ones. Existing tasks only get
+    /// the fields that differ from their original version, tasks without an
+    /// original version are replaced. New tasks are appended, so the order is kept.
+    /// Tasks that have an original version but no stored one have been deleted
+    /// by another instance and are skipped
+    pub fn merge_tasks(&mut self, data: Vec<Task>, originals: &[Task]) {
+        for task in data {
+            self.next_id = self.next_id.max(task.id + 1);
+            let original = originals.iter().find(|original| original.id == task.id);
+            match (
+                self.tasks.iter_mut().find(|stored| stored.id == task.id),
+                original,
+            ) {
+                (Some(stored), Some(original)) => stored.apply_changes(original, task),
+                (Some(stored), None) => *stored = task,
+                (None, Some(_)) => {}
+                (None, None) => self.tasks.push(task),
+            }
+        }
+    }
+
+    /// Removes all tasks with the given IDs. Dependencies on the removed
+    /// tasks are removed as well and their subtasks become top level tasks
+    pub fn remove_tasks(&mut self, ids: &[u64]) {
+        self.tasks.retain(|task| !ids.contains(&task.id));
+        for task in self.tasks.iter_mut() {
+            task.depends_on.retain(|id| !ids.contains(id));
+            if task.parent.is_some_and(|parent| ids.contains(&parent)) {
+                task.parent = None;
+            }
+        }
+    }
+
+    /// Renames the group in the list of groups.
+    /// The position of the group is kept
+    pub fn rename_group(&mut self, old: &str, new: &str) {
+        if let Some(group) = self.groups.iter_mut().find(|group| *group == old) {
+            *group = new.to_string();
+        }
+    }
+
+    /// Removes the group from the list of groups
+    pub fn remove_group(&mut self, name: &str) {
+        self.groups.retain(|group| group != name);
+    }
+}
+
+impl StorageHandler {
+    /// Creates a new instance of the storage service
+    /// and returns it. The backend can be selected with the
+    /// TERMINAL_TODO_STORAGE environment variable
+    pub fn new() -> Self {
+        let backend: Box<dyn StorageBackend> = match env::var("TERMINAL_TODO_STORAGE").as_deref() {
+            Ok("sqlite") => Box::new(SqliteBackend::new()),
+            _ => Box::new(JsonBackend::new()),
+        };
+        StorageHandler::with_backend(backend)
+    }
+
+    /// Creates a new instance of the storage service
+    /// that uses the given backend
+    pub fn with_backend(backend: Box<dyn StorageBackend>) -> Self {
+        StorageHandler {
+            backend,
+            originals: HashMap::new(),
+        }
+    }
+
+    /// Keeps the first version of the tasks that has been read. Writes
+    /// only apply the fields that have been changed since that version
+    fn remember(&mut self, tasks: &[Task]) {
+        for task in tasks.iter() {
+            self.originals
+                .entry(task.id)
+                .or_insert_with(|| task.clone());
+        }
+    }
+
+    /// Reads the data of the backend again on the next access.
+    /// Used by long running views to show changes of other instances
+    pub fn reload(&mut self) {
+        self.originals.clear();
+        self.backend.reload();
+    }
+
+    /// Gets all tasks that are provided by the backend
+    pub fn get_all_tasks(&mut self) -> AppResult<Vec<Task>> {
+        let tasks = self.backend.get_all_tasks()?;
+        self.remember(&tasks);
+        Ok(tasks)
+    }
+
+    /// Gets all groups that are provided by the backend.
+    /// Only the names are returned
+    pub fn get_all_groups(&mut self) -> AppResult<Vec<String>> {
+        self.backend.get_all_groups()
+    }
+
+    /// Gets a single task by its ID. If there is no task
+    /// with the given ID, None is returned
+    pub fn get_task_by_id(&mut self, id: u64) -> AppResult<Option<Task>> {
+        let task = self.backend.get_task_by_id(id)?;
+        self.remember(task.as_slice());
+        Ok(task)
+    }
+
+    /// Gets all tasks of the given group. If no group
+    /// is given, all tasks without a group are returned
+    pub fn get_tasks_by_group(&mut self, group: Option<&str>) -> AppResult<Vec<Task>> {
+        let tasks = self.backend.get_tasks_by_group(group)?;
+        self.remember(&tasks);
+        Ok(tasks)
+    }
+
+    /// Gets all tags that are used by tasks with the number of
+    /// tasks using them. The most used tags are listed first
+    pub fn get_tag_counts(&mut self) -> AppResult<Vec<(String, u64)>> {
+        self.backend.get_tag_counts()
+    }
+
+    /// Gets all direct subtasks of the task with the given ID
+    pub fn get_children(&mut self, id: u64) -> AppResult<Vec<Task>> {
+        Ok(self
+            .get_all_tasks()?
+            .into_iter()
+            .filter(|task| task.parent == Some(id))
+            .collect())
+    }
+
+    /// Checks if the tasks can be saved with their status. A task cannot
+    /// be done while it has open subtasks. Subtasks that are saved
+    /// together with their parent are respected
+    pub fn validate_finished(&mut self, tasks: &[Task]) -> AppResult<()> {
+        for task in tasks.iter().filter(|task| task.status == Status::Done) {
+            let open = self
+                .get_children(task.id)?
+                .iter()
+                .map(|child| {
+                    tasks
+                        .iter()
+                        .find(|updated| updated.id == child.id)
+                        .unwrap_or(child)
+                })
+                .filter(|child| !child.is_closed())
+                .count();
+            if open > 0 {
+                return Err(AppError::Validation(format!(
+                    "The task {} has {} open subtasks",
+                    task.title, open
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks if the parent of the task exists and is
+    /// neither the task itself nor one of its subtasks
+    pub fn validate_parent(&mut self, task: &Task) -> AppResult<()> {
+        let tasks = self.get_all_tasks()?;
+        let mut visited = HashSet::new();
+        let mut current = task.parent;
+        while let Some(id) = current {
+            if id == task.id {
+                return Err(AppError::Validation(format!(
+                    "The task {} cannot be a subtask of itself or its subtasks",
+                    task.title
+                )));
+            }
+            if !visited.insert(id) {
+                break;
+            }
+            current = match tasks.iter().find(|other| other.id == id) {
+                Some(parent) => parent.parent,
+                None => {
+                    return Err(AppError::NotFound(format!(
+                        "There is no task with ID {}",
+                        id
+                    )))
+                }
+            };
+        }
+        Ok(())
+    }
+
+    /// Checks if tasks with all the given IDs exist.
+    /// The first unknown ID is returned as not found error
+    pub fn validate_task_ids(&mut self, ids: &[u64]) -> AppResult<()> {
+        let tasks = self.get_all_tasks()?;
+        match ids.iter().find(|id| !self.id_exists_in_vec(&tasks, **id)) {
+            Some(id) => Err(AppError::NotFound(format!(
+                "There is no task with ID {}",
+                id
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks if all dependencies of the existing task
+    /// exist and do not lead back to the task itself
+    pub fn validate_dependencies(&mut self, task: &Task) -> AppResult<()> {
+        self.validate_task_ids(&task.depends_on)?;
+        if has_dependency_cycle(&self.get_all_tasks()?, task) {
+            return Err(AppError::Validation(format!(
+                "The dependencies of the task {} would create a cycle",
+                task.title
+            )));
+        }
+        Ok(())
+    }
+
+    /// Adds a single task to the big lists of tasks
+    /// and saves it. The ID of the new task is returned
+    pub fn add_single_task(&mut self, mut task: Task) -> AppResult<u64> {
+        task.stamp(None, Utc::now());
+        self.backend.add_single_task(task)
+    }
+
+    /// Adds a new task group and saves it.
+    pub fn add_task_group(&mut self, title: String) -> AppResult<()> {
+        self.backend.add_task_group(title)
+    }
+
+    /// Checks if an task with specific ID already exists
+    /// in vector.
+    pub fn id_exists_in_vec(&mut self, data: &[Task], id: u64) -> bool {
+        data.iter().any(|task| task.id == id)
+    }
+
+    /// Writes an vector of tasks into the backend. Only the fields that changed
+    /// since the tasks have been read are saved, so changes of other instances
+    /// are kept. The timestamps of changed tasks are updated
+    pub fn write_task_data(&mut self, mut data: Vec<Task>) -> AppResult<()> {
+        self.get_all_tasks()?;
+        let now = Utc::now();
+        let mut originals = vec![];
+        for task in data.iter_mut() {
+            let original = self.originals.get(&task.id);
+            task.stamp(original, now);
+            originals.extend(original.cloned());
+        }
+        self.backend.write_task_data(data.clone(), &originals)?;
+        for task in data {
+            self.originals.insert(task.id, task);
+        }
+        Ok(())
+    }
+
+    /// Saves the tasks like write_task_data. Recurring tasks that have just been
+    /// done are kept as record of the completion and their next occurrence is
+    /// added as new task. The new occurrences are returned with their IDs
+    pub fn write_completed_tasks(
+        &mut self,
+        data: Vec<Task>,
+        today: NaiveDate,
+    ) -> AppResult<Vec<Task>> {
+        let mut occurrences = vec![];
+        let data = data
+            .into_iter()
+            .map(|mut task| {
+                let was_done = self
+                    .originals
+                    .get(&task.id)
+                    .is_some_and(|original| original.status == Status::Done);
+                if task.status == Status::Done && !was_done {
+                    if let Some(recurrence) = task.recurrence.take() {
+                        occurrences.push(task.next_occurrence(recurrence, today));
+                    }
+                }
+                task
+            })
+            .collect::<Vec<Task>>();
+        self.write_task_data(data)?;
+        for occurrence in occurrences.iter_mut() {
+            occurrence.id = self.add_single_task(occurrence.clone())?;
+        }
+        Ok(occurrences)
+    }
+
+    /// Removes all tasks with the given IDs
+    pub fn delete_tasks(&mut self, ids: &[u64]) -> AppResult<()> {
+        self.backend.delete_tasks(ids)
+    }
+
+    /// Checks if the group exists. If not,
+    /// a not found error is returned
+    pub fn validate_group_exists(&mut self, name: &str) -> AppResult<()> {
+        match self.get_all_groups()?.iter().any(|group| group == name) {
+            true => Ok(()),
+            false => Err(AppError::NotFound(format!(
+                "The group {} does not exist",
+                name
+            ))),
+        }
+    }
+
+    /// Renames the group and moves all its tasks to the new name.
+    /// The new name must not be used by another group
+    pub fn rename_group(&mut self, old: &str, new: &str) -> AppResult<()> {
+        self.validate_group_exists(old)?;
+        if new.trim().is_empty() {
+            return Err(AppError::Validation(
+                "The group name must not be empty".to_string(),
+            ));
+        }
+        if self.get_all_groups()?.iter().any(|group| group == new) {
+            return Err(AppError::Validation(format!(
+                "The group {} already exists. Merge the groups instead",
+                new
+            )));
+        }
+        self.backend.rename_task_group(old, new)?;
+        self.move_group_tasks(old, Some(new))
+    }
+
+    /// Moves all tasks of the group to the other group.
+    /// Without a target group the tasks are ungrouped
+    pub fn move_group_tasks(&mut self, from: &str, to: Option<&str>) -> AppResult<()> {
+        let tasks = self
+            .get_tasks_by_group(Some(from))?
+            .into_iter()
+            .map(|task| Task {
+                group: to.map(String::from),
+                ..task
+            })
+            .collect::<Vec<Task>>();
+        self.write_task_data(tasks)
+    }
+
+    /// Removes the group. Its tasks have to be
+    /// moved or deleted before
+    pub fn delete_group(&mut self, name: &str) -> AppResult<()> {
+        self.backend.delete_task_group(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage_handler::memory_backend::MemoryBackend;
+
+    fn task(id: u64, title: &str) -> Task {
+        Task {
+            id,
+            ..Task::new(title.to_string(), None)
+        }
+    }
+
+    fn data_file(tasks: Vec<Task>) -> DataFile {
+        DataFile {
+            next_id: tasks.iter().map(|task| task.id + 1).max().unwrap_or(0),
+            tasks,
+            ..DataFile::default()
+        }
+    }
+
+    fn titles(tasks: &[Task]) -> Vec<&str> {
+        tasks.iter().map(|task| task.title.as_str()).collect()
+    }
+
+    #[test]
+    fn validate_accepts_unique_ids() {
+        assert!(data_file(vec![task(0, "a"), task(3, "b")])
+            .validate()
+            .is_ok());
+    }
+
+    #[test]
+    fn validate_refuses_duplicate_ids() {
+        let data = data_file(vec![task(1, "a"), task(1, "b")]);
+        assert!(matches!(data.validate(), Err(AppError::Parse(_))));
+    }
+
+    #[test]
+    fn validate_refuses_ids_above_the_counter() {
+        let data = DataFile {
+            next_id: 2,
+            ..data_file(vec![task(2, "a")])
+        };
+        assert!(matches!(data.validate(), Err(AppError::Parse(_))));
+    }
+
+    #[test]
+    fn merge_tasks_replaces_existing_and_appends_new_tasks() {
+        let mut data = data_file(vec![task(0, "a"), task(1, "b"), task(2, "c")]);
+        data.merge_tasks(vec![task(5, "new"), task(1, "changed")], &[]);
+        assert_eq!(titles(&data.tasks), ["a", "changed", "c", "new"]);
+        assert_eq!(data.next_id, 6);
+    }
+
+    #[test]
+    fn add_task_never_reuses_ids() {
+        let mut data = data_file(vec![task(0, "a"), task(1, "b")]);
+        data.remove_tasks(&[1]);
+        assert_eq!(data.add_task(task(0, "c")), 2);
+        assert_eq!(titles(&data.tasks), ["a", "c"]);
+    }
+
+    #[test]
+    fn merge_tasks_keeps_fields_changed_by_others() {
+        let original = task(0, "a");
+        let mut data = data_file(vec![Task {
+            status: Status::Done,
+            ..original.clone()
+        }]);
+        data.merge_tasks(vec![task(0, "renamed")], std::slice::from_ref(&original));
+        assert_eq!(data.tasks[0].title, "renamed");
+        assert_eq!(data.tasks[0].status, Status::Done);
+    }
+
+    #[test]
+    fn detects_dependency_cycles() {
+        let a = Task {
+            depends_on: vec![1],
+            ..task(0, "a")
+        };
+        let b = Task {
+            depends_on: vec![2],
+            ..task(1, "b")
+        };
+        let c = task(2, "c");
+        let tasks = vec![a.clone(), b, c.clone()];
+        assert!(!has_dependency_cycle(&tasks, &a));
+        let c = Task {
+            depends_on: vec![0],
+            ..c
+        };
+        assert!(has_dependency_cycle(&tasks, &c));
+        let own = Task {
+            depends_on: vec![0],
+            ..task(0, "a")
+        };
+        assert!(has_dependency_cycle(&tasks, &own));
+    }
+
+    #[test]
+    fn refuses_done_tasks_with_open_subtasks() {
+        let mut handler = StorageHandler::with_backend(Box::new(MemoryBackend::new()));
+        let parent = handler.add_single_task(task(0, "parent")).unwrap();
+        let child = handler
+            .add_single_task(Task {
+                parent: Some(parent),
+                ..task(0, "child")
+            })
+            .unwrap();
+        let done = |id: u64, title: &str| Task {
+            status: Status::Done,
+            ..task(id, title)
+        };
+        let result = handler.validate_finished(&[done(parent, "parent")]);
+        assert!(matches!(result, Err(AppError::Validation(_))));
+        let child = Task {
+            parent: Some(parent),
+            ..done(child, "child")
+        };
+        assert!(handler
+            .validate_finished(&[done(parent, "parent"), child])
+            .is_ok());
+    }
+
+    #[test]
+    fn completing_a_recurring_task_adds_the_next_occurrence() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let mut handler = StorageHandler::with_backend(Box::new(MemoryBackend::new()));
+        let id = handler
+            .add_single_task(Task {
+                due: Some(today),
+                recurrence: Some(Recurrence::Daily),
+                ..task(0, "water plants")
+            })
+            .unwrap();
+        let mut stored = handler.get_task_by_id(id).unwrap().unwrap();
+        stored.status = Status::Done;
+        let occurrences = handler.write_completed_tasks(vec![stored], today).unwrap();
+        assert_eq!(occurrences.len(), 1);
+        let next = handler.get_task_by_id(occurrences[0].id).unwrap().unwrap();
+        assert_eq!(next.due, today.succ_opt());
+        assert_eq!(next.recurrence, Some(Recurrence::Daily));
+        let done = handler.get_task_by_id(id).unwrap().unwrap();
+        assert!(done.recurrence.is_none());
+        assert!(done.completed_at.is_some());
+    }
+
+    #[test]
+    fn saving_a_done_recurring_task_again_adds_no_occurrence() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let mut handler = StorageHandler::with_backend(Box::new(MemoryBackend::new()));
+        let id = handler
+            .add_single_task(Task {
+                status: Status::Done,
+                recurrence: Some(Recurrence::Daily),
+                ..task(0, "water plants")
+            })
+            .unwrap();
+        let stored = handler.get_task_by_id(id).unwrap().unwrap();
+        let occurrences = handler.write_completed_tasks(vec![stored], today).unwrap();
+        assert!(occurrences.is_empty());
+        assert_eq!(handler.get_all_tasks().unwrap().len(), 1);
+    }
+}
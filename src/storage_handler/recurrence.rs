@@ -0,0 +1,239 @@
+use crate::error::{AppError, AppResult};
+use crate::inputs::due_date::parse_weekday;
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Defines when a recurring task occurs again. The rule
+/// is stored with the same text that is used as input,
+/// e.g. daily, weekly:mon,fri, monthly:15 or every:3d
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(try_from = "String", into = "String")]
+pub enum Recurrence {
+    Daily,
+    Weekly(Vec<Weekday>),
+    Monthly(u32),
+    AfterCompletion(u64),
+}
+
+impl Recurrence {
+    /// Parses the recurrence rule. Supported are daily,
+    /// weekly:<weekday,...>, monthly:<day> and every:<n>d
+    pub fn parse(input: &str) -> AppResult<Recurrence> {
+        let value = input.trim().to_lowercase();
+        let (kind, argument) = value.split_once(':').unwrap_or((value.as_str(), ""));
+        let parsed = match kind {
+            "daily" if argument.is_empty() => Some(Recurrence::Daily),
+            "weekly" => argument
+                .split(',')
+                .map(|day| parse_weekday(day.trim()))
+                .collect::<Option<Vec<Weekday>>>()
+                .map(Recurrence::Weekly),
+            "monthly" => argument
+                .parse::<u32>()
+                .ok()
+                .filter(|day| (1..=31).contains(day))
+                .map(Recurrence::Monthly),
+            "every" => argument
+                .strip_suffix('d')
+                .and_then(|days| days.parse::<u64>().ok())
+                .filter(|days| *days > 0)
+                .map(Recurrence::AfterCompletion),
+            _ => None,
+        };
+        parsed.ok_or_else(|| {
+            AppError::Validation(format!(
+                "{} is not a valid recurrence. Use daily, weekly:mon,fri, monthly:15 or every:3d",
+                input
+            ))
+        })
+    }
+
+    /// Checks if a scheduled recurrence occurs on the given date.
+    /// Recurrences after completion do not follow a schedule
+    fn occurs_on(&self, date: NaiveDate) -> bool {
+        match self {
+            Recurrence::Daily => true,
+            Recurrence::Weekly(days) => days.contains(&date.weekday()),
+            Recurrence::Monthly(day) => {
+                let last_day = date
+                    .with_day(1)
+                    .and_then(|first| first.checked_add_months(Months::new(1)))
+                    .and_then(|next| next.pred_opt())
+                    .map(|last| last.day())
+                    .unwrap_or(31);
+                date.day() == (*day).min(last_day)
+            }
+            Recurrence::AfterCompletion(_) => false,
+        }
+    }
+
+    /// Gets the first scheduled date after the given date
+    fn next_after(&self, date: NaiveDate) -> NaiveDate {
+        let mut next = date;
+        loop {
+            next = next.succ_opt().unwrap_or(next);
+            if self.occurs_on(next) {
+                return next;
+            }
+        }
+    }
+
+    /// Gets the due date of the first occurrence if it starts today.
+    /// Recurrences after completion have no first due date
+    pub fn first_due(&self, today: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Recurrence::AfterCompletion(_) => None,
+            _ => today.pred_opt().map(|yesterday| self.next_after(yesterday)),
+        }
+    }
+
+    /// Gets the due date of the occurrence after the one that has been
+    /// completed today. Occurrences that have been missed are skipped
+    pub fn next_due(&self, due: Option<NaiveDate>, today: NaiveDate) -> NaiveDate {
+        match self {
+            Recurrence::AfterCompletion(days) => {
+                today.checked_add_days(Days::new(*days)).unwrap_or(today)
+            }
+            _ => self.next_after(due.unwrap_or(today).max(today)),
+        }
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Recurrence::Daily => write!(f, "daily"),
+            Recurrence::Weekly(days) => {
+                let days = days
+                    .iter()
+                    .map(|day| day.to_string().to_lowercase())
+                    .collect::<Vec<String>>();
+                write!(f, "weekly:{}", days.join(","))
+            }
+            Recurrence::Monthly(day) => write!(f, "monthly:{}", day),
+            Recurrence::AfterCompletion(days) => write!(f, "every:{}d", days),
+        }
+    }
+}
+
+impl TryFrom<String> for Recurrence {
+    type Error = AppError;
+
+    fn try_from(value: String) -> AppResult<Recurrence> {
+        Recurrence::parse(&value)
+    }
+}
+
+impl From<Recurrence> for String {
+    fn from(recurrence: Recurrence) -> String {
+        recurrence.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn parses_all_rules() {
+        assert_eq!(Recurrence::parse("daily").unwrap(), Recurrence::Daily);
+        assert_eq!(
+            Recurrence::parse("weekly:mon,fri").unwrap(),
+            Recurrence::Weekly(vec![Weekday::Mon, Weekday::Fri])
+        );
+        assert_eq!(
+            Recurrence::parse("monthly:15").unwrap(),
+            Recurrence::Monthly(15)
+        );
+        assert_eq!(
+            Recurrence::parse("every:3d").unwrap(),
+            Recurrence::AfterCompletion(3)
+        );
+        for input in [
+            "",
+            "daily:2",
+            "weekly:",
+            "monthly:32",
+            "every:0d",
+            "every:3",
+        ] {
+            assert!(Recurrence::parse(input).is_err(), "{} is accepted", input);
+        }
+    }
+
+    #[test]
+    fn formats_like_the_input() {
+        for input in ["daily", "weekly:mon,fri", "monthly:15", "every:3d"] {
+            assert_eq!(Recurrence::parse(input).unwrap().to_string(), input);
+        }
+    }
+
+    #[test]
+    fn next_due_of_daily_is_the_next_day() {
+        let today = date(2026, 10, 18);
+        assert_eq!(
+            Recurrence::Daily.next_due(Some(today), today),
+            date(2026, 10, 19)
+        );
+        assert_eq!(Recurrence::Daily.next_due(None, today), date(2026, 10, 19));
+    }
+
+    #[test]
+    fn next_due_skips_missed_occurrences() {
+        let today = date(2026, 10, 18);
+        let weekly = Recurrence::Weekly(vec![Weekday::Wed]);
+        assert_eq!(
+            weekly.next_due(Some(date(2026, 10, 7)), today),
+            date(2026, 10, 21)
+        );
+    }
+
+    #[test]
+    fn next_due_keeps_the_schedule_of_early_completions() {
+        let today = date(2026, 10, 18);
+        let weekly = Recurrence::Weekly(vec![Weekday::Wed]);
+        assert_eq!(
+            weekly.next_due(Some(date(2026, 10, 21)), today),
+            date(2026, 10, 28)
+        );
+    }
+
+    #[test]
+    fn next_due_of_monthly_uses_the_last_day_of_short_months() {
+        let monthly = Recurrence::Monthly(31);
+        assert_eq!(
+            monthly.next_due(Some(date(2026, 1, 31)), date(2026, 1, 31)),
+            date(2026, 2, 28)
+        );
+        assert_eq!(
+            monthly.next_due(Some(date(2026, 2, 28)), date(2026, 2, 28)),
+            date(2026, 3, 31)
+        );
+    }
+
+    #[test]
+    fn next_due_after_completion_counts_from_today() {
+        let today = date(2026, 10, 18);
+        let every = Recurrence::AfterCompletion(3);
+        assert_eq!(
+            every.next_due(Some(date(2026, 10, 1)), today),
+            date(2026, 10, 21)
+        );
+        assert_eq!(every.first_due(today), None);
+    }
+
+    #[test]
+    fn first_due_can_be_today() {
+        let today = date(2026, 10, 18);
+        assert_eq!(Recurrence::Daily.first_due(today), Some(today));
+        assert_eq!(
+            Recurrence::Monthly(1).first_due(today),
+            Some(date(2026, 11, 1))
+        );
+    }
+}
@@ -0,0 +1,378 @@
+use crate::error::{AppError, AppResult};
+use crate::storage_handler::migrations::migrate_finished_status;
+use crate::storage_handler::{has_dependency_cycle, DataFile, Task};
+use std::collections::{HashMap, HashSet};
+
+/// Defines the result of checking the raw data file.
+/// Contains the repaired data and all problems that have been found
+pub struct RepairResult {
+    pub data: DataFile,
+    pub problems: Vec<String>,
+}
+
+/// Checks the raw content of the data file and repairs it.
+/// If the content cannot be parsed, every task that still parses is salvaged.
+/// Files written by a newer version of the cli are never repaired
+pub fn check_data(raw: &str) -> AppResult<RepairResult> {
+    let mut problems = vec![];
+    let mut data = match DataFile::from_json_unchecked(raw) {
+        Ok(data) => data,
+        Err(AppError::Validation(message)) => return Err(AppError::Validation(message)),
+        Err(e) => {
+            problems.push(e.to_string());
+            salvage_data(raw, &mut problems)
+        }
+    };
+    fix_duplicate_groups(&mut data, &mut problems);
+    fix_duplicate_ids(&mut data, &mut problems);
+    fix_missing_groups(&mut data, &mut problems);
+    fix_missing_parents(&mut data, &mut problems);
+    fix_broken_dependencies(&mut data, &mut problems);
+    Ok(RepairResult { data, problems })
+}
+
+/// Finds the index of the bracket that closes the object or array
+/// starting at the given index. Brackets in strings are ignored
+fn find_closing_bracket(raw: &[u8], start: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in raw.iter().enumerate().skip(start) {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if *c == b'\\' {
+                escaped = true;
+            } else if *c == b'"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match c {
+            b'"' => in_string = true,
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Salvages all task objects, the group list and the ID
+/// counter that can still be parsed from the raw content
+fn salvage_data(raw: &str, problems: &mut Vec<String>) -> DataFile {
+    let bytes = raw.as_bytes();
+    let mut data = DataFile::default();
+    let mut start = 0;
+    while let Some(offset) = raw[start..].find('{') {
+        let object_start = start + offset;
+        start = object_start + 1;
+        if let Some(end) = find_closing_bracket(bytes, object_start) {
+            if let Ok(mut value) =
+                serde_json::from_str::<serde_json::Value>(&raw[object_start..=end])
+            {
+                migrate_finished_status(&mut value);
+                if let Ok(task) = serde_json::from_value::<Task>(value) {
+                    data.tasks.push(task);
+                    start = end + 1;
+                }
+            }
+        }
+    }
+    if let Some(offset) = raw.find("\"groups\"") {
+        if let Some(array_start) = raw[offset..].find('[').map(|i| offset + i) {
+            if let Some(end) = find_closing_bracket(bytes, array_start) {
+                data.groups = serde_json::from_str(&raw[array_start..=end]).unwrap_or_default();
+            }
+        }
+    }
+    if let Some(offset) = raw.find("\"next_id\"") {
+        let value = raw[offset + "\"next_id\"".len()..].trim_start();
+        if let Some(value) = value.strip_prefix(':').map(str::trim_start) {
+            let digits = value
+                .chars()
+                .take_while(char::is_ascii_digit)
+                .collect::<String>();
+            data.next_id = digits.parse().unwrap_or_default();
+        }
+    }
+    problems.push(format!(
+        "Salvaged {} tasks and {} groups",
+        data.tasks.len(),
+        data.groups.len()
+    ));
+    data
+}
+
+/// Removes group names that are listed more than once
+fn fix_duplicate_groups(data: &mut DataFile, problems: &mut Vec<String>) {
+    let mut seen = HashSet::new();
+    data.groups.retain(|group| {
+        if seen.insert(group.clone()) {
+            return true;
+        }
+        problems.push(format!("The group {} is listed more than once", group));
+        false
+    });
+}
+
+/// Assigns new IDs to all tasks that share their ID with a task
+/// listed before them and moves the ID counter behind all used IDs
+fn fix_duplicate_ids(data: &mut DataFile, problems: &mut Vec<String>) {
+    let max_id = data.tasks.iter().map(|task| task.id + 1).max().unwrap_or(0);
+    if data.next_id < max_id {
+        problems.push(format!(
+            "The next ID {} is already in use and is set to {}",
+            data.next_id, max_id
+        ));
+    }
+    let mut next_id = data.next_id.max(max_id);
+    let mut seen = HashSet::new();
+    for task in data.tasks.iter_mut() {
+        if !seen.insert(task.id) {
+            problems.push(format!(
+                "The task {} has the duplicate ID {} and gets the new ID {}",
+                task.title, task.id, next_id
+            ));
+            task.id = next_id;
+            seen.insert(next_id);
+            next_id += 1;
+        }
+    }
+    data.next_id = next_id;
+}
+
+/// Recreates all groups that are referenced
+/// by tasks but missing in the group list
+fn fix_missing_groups(data: &mut DataFile, problems: &mut Vec<String>) {
+    for task in data.tasks.iter() {
+        if let Some(group) = &task.group {
+            if !data.groups.contains(group) {
+                problems.push(format!(
+                    "The task {} references the missing group {}, the group is recreated",
+                    task.title, group
+                ));
+                data.groups.push(group.clone());
+            }
+        }
+    }
+}
+
+/// Removes the parent of all tasks whose parent task does not exist anymore.
+/// Cycles are broken at their first task, the other tasks of the cycle and
+/// subtasks below it keep their parent. The affected tasks become top level tasks
+fn fix_missing_parents(data: &mut DataFile, problems: &mut Vec<String>) {
+    let ids = data
+        .tasks
+        .iter()
+        .map(|task| task.id)
+        .collect::<HashSet<u64>>();
+    for task in data.tasks.iter_mut() {
+        if let Some(parent) = task.parent {
+            if !ids.contains(&parent) || parent == task.id {
+                problems.push(format!(
+                    "The task {} references the missing parent task {}, it becomes a top level task",
+                    task.title, parent
+                ));
+                task.parent = None;
+            }
+        }
+    }
+    let mut parents = data
+        .tasks
+        .iter()
+        .map(|task| (task.id, task.parent))
+        .collect::<HashMap<u64, Option<u64>>>();
+    for task in data.tasks.iter_mut() {
+        let mut visited = HashSet::new();
+        let mut current = task.parent;
+        while let Some(id) = current {
+            if id == task.id {
+                problems.push(format!(
+                    "The task {} is its own ancestor, it becomes a top level task",
+                    task.title
+                ));
+                task.parent = None;
+                parents.insert(task.id, None);
+                break;
+            }
+            if !visited.insert(id) {
+                break;
+            }
+            current = parents.get(&id).copied().flatten();
+        }
+    }
+}
+
+/// Removes all dependencies on tasks that do not exist anymore.
+/// Tasks whose dependencies lead back to themselves lose all dependencies
+fn fix_broken_dependencies(data: &mut DataFile, problems: &mut Vec<String>) {
+    let ids = data
+        .tasks
+        .iter()
+        .map(|task| task.id)
+        .collect::<HashSet<u64>>();
+    for task in data.tasks.iter_mut() {
+        for id in task.depends_on.iter().filter(|id| !ids.contains(id)) {
+            problems.push(format!(
+                "The task {} depends on the missing task {}",
+                task.title, id
+            ));
+        }
+        task.depends_on.retain(|id| ids.contains(id));
+    }
+    for index in 0..data.tasks.len() {
+        if has_dependency_cycle(&data.tasks, &data.tasks[index]) {
+            problems.push(format!(
+                "The dependencies of the task {} form a cycle and are removed",
+                data.tasks[index].title
+            ));
+            data.tasks[index].depends_on.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage_handler::migrations::CURRENT_VERSION;
+    use crate::storage_handler::status::Status;
+    use serde_json::json;
+
+    fn check(data: serde_json::Value) -> RepairResult {
+        check_data(&data.to_string()).unwrap()
+    }
+
+    fn task(id: u64, title: &str) -> serde_json::Value {
+        json!({ "id": id, "status": "todo", "title": title, "group": null })
+    }
+
+    fn file(tasks: Vec<serde_json::Value>, groups: Vec<&str>) -> serde_json::Value {
+        json!({ "version": CURRENT_VERSION, "next_id": 10, "tasks": tasks, "groups": groups })
+    }
+
+    #[test]
+    fn finds_no_problems_in_valid_data() {
+        let result = check(file(vec![task(0, "a"), task(1, "b")], vec!["work"]));
+        assert!(result.problems.is_empty());
+        assert_eq!(result.data.tasks.len(), 2);
+    }
+
+    #[test]
+    fn assigns_new_ids_to_duplicates() {
+        let result = check(json!({
+            "version": CURRENT_VERSION,
+            "next_id": 1,
+            "tasks": [task(3, "a"), task(3, "b")],
+            "groups": []
+        }));
+        let ids = result
+            .data
+            .tasks
+            .iter()
+            .map(|task| task.id)
+            .collect::<Vec<u64>>();
+        assert_eq!(ids, [3, 4]);
+        assert_eq!(result.data.next_id, 5);
+        assert!(result.data.validate().is_ok());
+    }
+
+    #[test]
+    fn recreates_missing_groups_and_removes_duplicates() {
+        let mut grouped = task(0, "a");
+        grouped["group"] = json!("home");
+        let result = check(file(vec![grouped], vec!["work", "work"]));
+        assert_eq!(result.data.groups, ["work", "home"]);
+        assert_eq!(result.problems.len(), 2);
+    }
+
+    #[test]
+    fn detaches_tasks_with_missing_parents() {
+        let mut child = task(1, "child");
+        child["parent"] = json!(7);
+        let result = check(file(vec![task(0, "a"), child], vec![]));
+        assert_eq!(result.data.tasks[1].parent, None);
+        assert_eq!(result.problems.len(), 1);
+    }
+
+    #[test]
+    fn breaks_parent_cycles_only_at_their_tasks() {
+        let mut child = task(0, "child");
+        child["parent"] = json!(2);
+        let mut a = task(1, "a");
+        a["parent"] = json!(3);
+        let mut b = task(2, "b");
+        b["parent"] = json!(3);
+        let mut c = task(3, "c");
+        c["parent"] = json!(2);
+        let result = check(file(vec![child, a, b, c], vec![]));
+        let parents = result
+            .data
+            .tasks
+            .iter()
+            .map(|task| task.parent)
+            .collect::<Vec<Option<u64>>>();
+        assert_eq!(parents, [Some(2), Some(3), None, Some(2)]);
+        assert_eq!(result.problems.len(), 1);
+    }
+
+    #[test]
+    fn removes_broken_dependencies() {
+        let mut a = task(0, "a");
+        a["depends_on"] = json!([1, 9]);
+        let mut b = task(1, "b");
+        b["depends_on"] = json!([0]);
+        let result = check(file(vec![a, b], vec![]));
+        assert!(result
+            .data
+            .tasks
+            .iter()
+            .all(|task| !task.depends_on.contains(&9)));
+        assert!(!result
+            .data
+            .tasks
+            .iter()
+            .any(|task| has_dependency_cycle(&result.data.tasks, task)));
+    }
+
+    #[test]
+    fn salvages_tasks_of_corrupt_data() {
+        let raw = r#"{"version": 9, "tasks": [
+            {"id": 0, "finished": true, "title": "kept {brace}", "group": null},
+            {"id": 1, "finished": false, "title":
+        ], "groups": ["work"]"#;
+        let result = check_data(raw).unwrap();
+        assert_eq!(result.data.tasks.len(), 1);
+        assert_eq!(result.data.tasks[0].title, "kept {brace}");
+        assert_eq!(result.data.tasks[0].status, Status::Done);
+        assert_eq!(result.data.groups, ["work"]);
+        assert_eq!(result.data.next_id, 1);
+        assert!(result
+            .problems
+            .iter()
+            .any(|problem| problem.starts_with("Salvaged 1 tasks")));
+    }
+
+    #[test]
+    fn salvages_the_id_counter() {
+        let raw = r#"{"version": 11, "next_id": 7, "tasks": [
+            {"id": 0, "status": "todo", "title": "kept", "group": null},
+            {"id": 1,"#;
+        let result = check_data(raw).unwrap();
+        assert_eq!(result.data.next_id, 7);
+        let raw = r#"{"version": 11, "next_id": 1, "tasks": [
+            {"id": 4, "status": "todo", "title": "kept", "group": null},"#;
+        assert_eq!(check_data(raw).unwrap().data.next_id, 5);
+    }
+
+    #[test]
+    fn refuses_newer_data() {
+        let raw = json!({ "version": 99, "tasks": [], "groups": [] }).to_string();
+        assert!(matches!(check_data(&raw), Err(AppError::Validation(_))));
+    }
+}
@@ -0,0 +1,590 @@
+use crate::error::AppResult;
+use crate::storage_handler::backend::StorageBackend;
+use crate::storage_handler::lock::DataLock;
+use crate::storage_handler::recurrence::Recurrence;
+use crate::storage_handler::repair;
+use crate::storage_handler::status::Status;
+use crate::storage_handler::{get_data_dir, DataFile, Priority, Task};
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSqlOutput, ValueRef};
+use rusqlite::{params, params_from_iter, Connection, Row, ToSql, TransactionBehavior};
+use std::fs;
+use std::path::Path;
+
+/// The schema of the database. All statements can be
+/// executed on every start, because existing tables are kept
+const SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS tasks (
+        id INTEGER PRIMARY KEY,
+        finished INTEGER NOT NULL DEFAULT 0,
+        title TEXT NOT NULL,
+        group_name TEXT
+    );
+    CREATE TABLE IF NOT EXISTS groups (
+        name TEXT PRIMARY KEY
+    );
+    CREATE TABLE IF NOT EXISTS meta (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS tasks_group_name ON tasks (group_name);
+"#;
+
+/// Changes of the schema after the first release. The migration
+/// at index 0 upgrades the database from user_version 0 to 1
+const SCHEMA_MIGRATIONS: [&str; 9] = [
+    r#"
+    ALTER TABLE tasks ADD COLUMN due TEXT;
+    CREATE INDEX tasks_due ON tasks (due);
+    "#,
+    r#"
+    ALTER TABLE tasks ADD COLUMN priority INTEGER NOT NULL DEFAULT 0;
+    "#,
+    r#"
+    CREATE TABLE task_tags (
+        task_id INTEGER NOT NULL,
+        tag TEXT NOT NULL,
+        PRIMARY KEY (task_id, tag)
+    );
+    CREATE INDEX task_tags_tag ON task_tags (tag);
+    "#,
+    r#"
+    ALTER TABLE tasks ADD COLUMN notes TEXT;
+    "#,
+    r#"
+    ALTER TABLE tasks ADD COLUMN parent INTEGER;
+    CREATE INDEX tasks_parent ON tasks (parent);
+    "#,
+    r#"
+    CREATE TABLE task_dependencies (
+        task_id INTEGER NOT NULL,
+        depends_on INTEGER NOT NULL,
+        PRIMARY KEY (task_id, depends_on)
+    );
+    CREATE INDEX task_dependencies_depends_on ON task_dependencies (depends_on);
+    "#,
+    r#"
+    ALTER TABLE tasks ADD COLUMN recurrence TEXT;
+    "#,
+    r#"
+    ALTER TABLE tasks ADD COLUMN status TEXT NOT NULL DEFAULT 'todo';
+    UPDATE tasks SET status = 'done' WHERE finished = 1;
+    DROP INDEX IF EXISTS tasks_finished;
+    ALTER TABLE tasks DROP COLUMN finished;
+    CREATE INDEX tasks_status ON tasks (status);
+    "#,
+    r#"
+    ALTER TABLE tasks ADD COLUMN created_at TEXT;
+    ALTER TABLE tasks ADD COLUMN updated_at TEXT;
+    ALTER TABLE tasks ADD COLUMN completed_at TEXT;
+    CREATE INDEX tasks_completed_at ON tasks (completed_at);
+    "#,
+];
+
+/// All columns of the tasks table in the order
+/// they are mapped to the fields of a task
+const TASK_COLUMNS: &str = "id, status, title, group_name, due, priority, notes, parent, recurrence, created_at, updated_at, completed_at";
+
+/// Selects the tags of a task as a single space separated value, because
+/// tags never contain whitespace. The tags are kept in the order they were added
+const TAGS_COLUMN: &str =
+    "(SELECT group_concat(tag, ' ' ORDER BY rowid) FROM task_tags WHERE task_id = tasks.id)";
+
+/// Selects the IDs of all tasks a task depends on as a single
+/// space separated value in the order they were added
+const DEPENDENCIES_COLUMN: &str =
+    "(SELECT group_concat(depends_on, ' ' ORDER BY rowid) FROM task_dependencies WHERE task_id = tasks.id)";
+
+impl ToSql for Priority {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(*self as i64))
+    }
+}
+
+impl FromSql for Priority {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let index = value.as_i64()?;
+        Priority::ALL
+            .get(index as usize)
+            .copied()
+            .ok_or(FromSqlError::OutOfRange(index))
+    }
+}
+
+impl ToSql for Recurrence {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.to_string()))
+    }
+}
+
+impl FromSql for Recurrence {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        Recurrence::parse(value.as_str()?).map_err(|e| FromSqlError::Other(Box::new(e)))
+    }
+}
+
+impl ToSql for Status {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.name()))
+    }
+}
+
+impl FromSql for Status {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        Status::parse(value.as_str()?).map_err(|e| FromSqlError::Other(Box::new(e)))
+    }
+}
+
+/// Storage backend that keeps all data in an embedded
+/// sqlite database in the app data directory
+#[derive(Default)]
+pub struct SqliteBackend {
+    connection: Option<Connection>,
+}
+
+impl SqliteBackend {
+    /// Creates a new instance of the sqlite backend.
+    /// The database is only opened on the first access
+    pub fn new() -> Self {
+        SqliteBackend::default()
+    }
+
+    /// Gets the connection to the data.db database. On the first call the
+    /// database is opened and the schema created if required. An existing
+    /// data.json file is imported once on the first start.
+    /// Concurrent writes of other instances are handled by sqlite itself
+    fn connection(&mut self) -> AppResult<&mut Connection> {
+        if self.connection.is_none() {
+            let root_dir = get_data_dir()?;
+            if !root_dir.exists() {
+                fs::create_dir_all(&root_dir)?;
+            }
+            let _lock = DataLock::acquire(&root_dir)?;
+            let mut connection = Connection::open(root_dir.join("data.db"))?;
+            connection.busy_timeout(DataLock::get_timeout())?;
+            connection.execute_batch(SCHEMA)?;
+            SqliteBackend::migrate_schema(&mut connection)?;
+            SqliteBackend::migrate_json_file(&mut connection, &root_dir.join("data.json"))?;
+            self.connection = Some(connection);
+        }
+        Ok(self.connection.as_mut().unwrap())
+    }
+
+    /// Runs all schema migrations that have not
+    /// been applied to the database yet
+    fn migrate_schema(connection: &mut Connection) -> AppResult<()> {
+        let version: usize = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        for (index, migration) in SCHEMA_MIGRATIONS.iter().enumerate().skip(version) {
+            let transaction = connection.transaction()?;
+            transaction.execute_batch(migration)?;
+            transaction.pragma_update(None, "user_version", index + 1)?;
+            transaction.commit()?;
+        }
+        Ok(())
+    }
+
+    /// Imports all tasks and groups of the given data.json file. A damaged
+    /// file is imported with all tasks that can be salvaged. Files that
+    /// cannot be imported at all are skipped and kept for the doctor.
+    /// The import is only done once and recorded in the meta table
+    fn migrate_json_file(connection: &mut Connection, path: &Path) -> AppResult<()> {
+        let migrated: bool = connection.query_row(
+            "SELECT COUNT(*) > 0 FROM meta WHERE key = 'json_migrated'",
+            [],
+            |row| row.get(0),
+        )?;
+        if migrated || !path.exists() {
+            return Ok(());
+        }
+        let raw = fs::read_to_string(path)?;
+        let data = match DataFile::from_json(&raw) {
+            Ok(data) => data,
+            Err(_) => match repair::check_data(&raw) {
+                Ok(result) => {
+                    eprintln!("WARNING: The data.json file is damaged, only the salvaged tasks are imported:");
+                    for problem in result.problems.iter() {
+                        eprintln!("- {}", problem);
+                    }
+                    result.data
+                }
+                Err(error) => {
+                    eprintln!("WARNING: The data.json file is not imported. {}", error);
+                    return Ok(());
+                }
+            },
+        };
+        let transaction = connection.transaction()?;
+        for group in data.groups.iter() {
+            transaction.execute(
+                "INSERT OR IGNORE INTO groups (name) VALUES (?1)",
+                params![group],
+            )?;
+        }
+        for task in data.tasks.iter() {
+            SqliteBackend::upsert_task(&transaction, task)?;
+        }
+        SqliteBackend::set_next_id(&transaction, data.next_id)?;
+        transaction.execute(
+            "INSERT INTO meta (key, value) VALUES ('json_migrated', '1')",
+            [],
+        )?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    /// Gets the next task ID from the meta table. IDs are never reused,
+    /// so the ID is at least one above the highest ID in use
+    fn get_next_id(connection: &Connection) -> AppResult<u64> {
+        let next_id = connection.query_row(
+            "SELECT MAX(
+                COALESCE((SELECT CAST(value AS INTEGER) FROM meta WHERE key = 'next_id'), 0),
+                COALESCE((SELECT MAX(id) + 1 FROM tasks), 0)
+            )",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(next_id)
+    }
+
+    /// Stores the next task ID in the meta table
+    fn set_next_id(connection: &Connection, next_id: u64) -> AppResult<()> {
+        connection.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES ('next_id', ?1)",
+            params![next_id.to_string()],
+        )?;
+        Ok(())
+    }
+
+    /// Inserts the task or replaces the existing
+    /// task with the same ID
+    fn upsert_task(connection: &Connection, task: &Task) -> AppResult<()> {
+        connection.execute(
+            &format!(
+                "INSERT OR REPLACE INTO tasks ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                TASK_COLUMNS
+            ),
+            params![
+                task.id,
+                task.status,
+                task.title,
+                task.group,
+                task.due,
+                task.priority,
+                task.notes,
+                task.parent,
+                task.recurrence,
+                task.created_at,
+                task.updated_at,
+                task.completed_at
+            ],
+        )?;
+        connection.execute("DELETE FROM task_tags WHERE task_id = ?1", params![task.id])?;
+        for tag in task.tags.iter() {
+            connection.execute(
+                "INSERT OR IGNORE INTO task_tags (task_id, tag) VALUES (?1, ?2)",
+                params![task.id, tag],
+            )?;
+        }
+        connection.execute(
+            "DELETE FROM task_dependencies WHERE task_id = ?1",
+            params![task.id],
+        )?;
+        for id in task.depends_on.iter() {
+            connection.execute(
+                "INSERT OR IGNORE INTO task_dependencies (task_id, depends_on) VALUES (?1, ?2)",
+                params![task.id, id],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Maps a single row of the tasks table to a task
+    fn task_from_row(row: &Row) -> rusqlite::Result<Task> {
+        Ok(Task {
+            id: row.get(0)?,
+            status: row.get(1)?,
+            title: row.get(2)?,
+            group: row.get(3)?,
+            due: row.get(4)?,
+            priority: row.get(5)?,
+            notes: row.get(6)?,
+            parent: row.get(7)?,
+            recurrence: row.get(8)?,
+            created_at: row.get(9)?,
+            updated_at: row.get(10)?,
+            completed_at: row.get(11)?,
+            tags: row
+                .get::<_, Option<String>>(12)?
+                .map(|tags| tags.split(' ').map(String::from).collect())
+                .unwrap_or_default(),
+            depends_on: row
+                .get::<_, Option<String>>(13)?
+                .map(|ids| ids.split(' ').filter_map(|id| id.parse().ok()).collect())
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Runs the given query on the tasks table of the
+    /// connection and returns all matching tasks
+    fn select_tasks<P: rusqlite::Params>(
+        connection: &Connection,
+        condition: &str,
+        params: P,
+    ) -> AppResult<Vec<Task>> {
+        let sql = format!(
+            "SELECT {}, {}, {} FROM tasks {} ORDER BY id",
+            TASK_COLUMNS, TAGS_COLUMN, DEPENDENCIES_COLUMN, condition
+        );
+        let mut statement = connection.prepare(&sql)?;
+        let rows = statement.query_map(params, SqliteBackend::task_from_row)?;
+        Ok(rows.collect::<rusqlite::Result<Vec<Task>>>()?)
+    }
+
+    /// Runs the given query on the tasks table and
+    /// returns all matching tasks
+    fn query_tasks<P: rusqlite::Params>(
+        &mut self,
+        condition: &str,
+        params: P,
+    ) -> AppResult<Vec<Task>> {
+        SqliteBackend::select_tasks(self.connection()?, condition, params)
+    }
+}
+
+impl StorageBackend for SqliteBackend {
+    fn get_all_tasks(&mut self) -> AppResult<Vec<Task>> {
+        self.query_tasks("", [])
+    }
+
+    fn get_all_groups(&mut self) -> AppResult<Vec<String>> {
+        let mut statement = self
+            .connection()?
+            .prepare("SELECT name FROM groups ORDER BY rowid")?;
+        let rows = statement.query_map([], |row| row.get(0))?;
+        Ok(rows.collect::<rusqlite::Result<Vec<String>>>()?)
+    }
+
+    fn add_single_task(&mut self, mut task: Task) -> AppResult<u64> {
+        let transaction = self
+            .connection()?
+            .transaction_with_behavior(TransactionBehavior::Immediate)?;
+        task.id = SqliteBackend::get_next_id(&transaction)?;
+        SqliteBackend::upsert_task(&transaction, &task)?;
+        SqliteBackend::set_next_id(&transaction, task.id + 1)?;
+        transaction.commit()?;
+        Ok(task.id)
+    }
+
+    fn add_task_group(&mut self, title: String) -> AppResult<()> {
+        self.connection()?.execute(
+            "INSERT OR IGNORE INTO groups (name) VALUES (?1)",
+            params![title],
+        )?;
+        Ok(())
+    }
+
+    fn write_task_data(&mut self, data: Vec<Task>, originals: &[Task]) -> AppResult<()> {
+        let transaction = self
+            .connection()?
+            .transaction_with_behavior(TransactionBehavior::Immediate)?;
+        for task in data {
+            let original = originals.iter().find(|original| original.id == task.id);
+            let stored =
+                SqliteBackend::select_tasks(&transaction, "WHERE id = ?1", params![task.id])?.pop();
+            let task = match (stored, original) {
+                (Some(mut stored), Some(original)) => {
+                    stored.apply_changes(original, task);
+                    stored
+                }
+                (None, Some(_)) => continue,
+                (_, None) => task,
+            };
+            SqliteBackend::upsert_task(&transaction, &task)?;
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn delete_tasks(&mut self, ids: &[u64]) -> AppResult<()> {
+        let placeholders = vec!["?"; ids.len()].join(", ");
+        let transaction = self.connection()?.transaction()?;
+        transaction.execute(
+            &format!("DELETE FROM tasks WHERE id IN ({})", placeholders),
+            params_from_iter(ids.iter()),
+        )?;
+        transaction.execute(
+            &format!("DELETE FROM task_tags WHERE task_id IN ({})", placeholders),
+            params_from_iter(ids.iter()),
+        )?;
+        transaction.execute(
+            &format!(
+                "DELETE FROM task_dependencies WHERE task_id IN ({0}) OR depends_on IN ({0})",
+                placeholders
+            ),
+            params_from_iter(ids.iter().chain(ids.iter())),
+        )?;
+        transaction.execute(
+            &format!(
+                "UPDATE tasks SET parent = NULL WHERE parent IN ({})",
+                placeholders
+            ),
+            params_from_iter(ids.iter()),
+        )?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn rename_task_group(&mut self, old: &str, new: &str) -> AppResult<()> {
+        self.connection()?.execute(
+            "UPDATE groups SET name = ?2 WHERE name = ?1",
+            params![old, new],
+        )?;
+        Ok(())
+    }
+
+    fn delete_task_group(&mut self, name: &str) -> AppResult<()> {
+        self.connection()?
+            .execute("DELETE FROM groups WHERE name = ?1", params![name])?;
+        Ok(())
+    }
+
+    fn get_task_by_id(&mut self, id: u64) -> AppResult<Option<Task>> {
+        Ok(self.query_tasks("WHERE id = ?1", params![id])?.pop())
+    }
+
+    fn get_tasks_by_group(&mut self, group: Option<&str>) -> AppResult<Vec<Task>> {
+        match group {
+            Some(name) => self.query_tasks("WHERE group_name = ?1", params![name]),
+            None => self.query_tasks("WHERE group_name IS NULL", []),
+        }
+    }
+
+    fn get_tag_counts(&mut self) -> AppResult<Vec<(String, u64)>> {
+        let mut statement = self.connection()?.prepare(
+            "SELECT tag, COUNT(*) AS count FROM task_tags GROUP BY tag ORDER BY count DESC, tag",
+        )?;
+        let rows = statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(rows.collect::<rusqlite::Result<Vec<(String, u64)>>>()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage_handler::StorageHandler;
+
+    fn user_version(connection: &Connection) -> usize {
+        connection
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn migrates_new_databases_to_the_latest_version() {
+        let mut connection = Connection::open_in_memory().unwrap();
+        connection.execute_batch(SCHEMA).unwrap();
+        SqliteBackend::migrate_schema(&mut connection).unwrap();
+        assert_eq!(user_version(&connection), SCHEMA_MIGRATIONS.len());
+        SqliteBackend::migrate_schema(&mut connection).unwrap();
+        assert_eq!(user_version(&connection), SCHEMA_MIGRATIONS.len());
+    }
+
+    #[test]
+    fn migrates_existing_rows() {
+        let mut connection = Connection::open_in_memory().unwrap();
+        connection.execute_batch(SCHEMA).unwrap();
+        connection
+            .execute_batch(
+                "INSERT INTO tasks (id, finished, title) VALUES (0, 1, 'done');
+                 INSERT INTO tasks (id, finished, title, group_name) VALUES (1, 0, 'open', 'work');",
+            )
+            .unwrap();
+        SqliteBackend::migrate_schema(&mut connection).unwrap();
+        let tasks = SqliteBackend::select_tasks(&connection, "", []).unwrap();
+        assert_eq!(tasks[0].status, Status::Done);
+        assert_eq!(tasks[1].status, Status::Todo);
+        assert_eq!(tasks[1].group.as_deref(), Some("work"));
+        assert!(tasks[1].due.is_none());
+        assert!(tasks[1].priority == Priority::None);
+        assert!(tasks[1].tags.is_empty() && tasks[1].created_at.is_none());
+    }
+
+    #[test]
+    fn continues_partially_migrated_databases() {
+        let mut connection = Connection::open_in_memory().unwrap();
+        connection.execute_batch(SCHEMA).unwrap();
+        for migration in SCHEMA_MIGRATIONS.iter().take(3) {
+            connection.execute_batch(migration).unwrap();
+        }
+        connection.pragma_update(None, "user_version", 3).unwrap();
+        SqliteBackend::migrate_schema(&mut connection).unwrap();
+        assert_eq!(user_version(&connection), SCHEMA_MIGRATIONS.len());
+    }
+
+    #[test]
+    fn imports_salvaged_tasks_of_a_damaged_json_file() {
+        let path =
+            std::env::temp_dir().join(format!("terminal-todo-import-{}.json", std::process::id()));
+        fs::write(
+            &path,
+            r#"{"version": 11, "next_id": 2, "tasks": [
+                {"id": 0, "status": "todo", "title": "kept", "group": null},
+                {"id": 1, "status": "#,
+        )
+        .unwrap();
+        let mut connection = Connection::open_in_memory().unwrap();
+        connection.execute_batch(SCHEMA).unwrap();
+        SqliteBackend::migrate_schema(&mut connection).unwrap();
+        SqliteBackend::migrate_json_file(&mut connection, &path).unwrap();
+        fs::remove_file(&path).unwrap();
+        let title: String = connection
+            .query_row("SELECT title FROM tasks WHERE id = 0", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(title, "kept");
+    }
+
+    #[test]
+    fn keeps_the_order_of_tags_and_dependencies() {
+        let mut connection = Connection::open_in_memory().unwrap();
+        connection.execute_batch(SCHEMA).unwrap();
+        SqliteBackend::migrate_schema(&mut connection).unwrap();
+        let task = Task {
+            tags: vec!["zeta".to_string(), "alpha".to_string(), "mid".to_string()],
+            depends_on: vec![9, 2, 5],
+            ..Task::new("ordered".to_string(), None)
+        };
+        SqliteBackend::upsert_task(&connection, &task).unwrap();
+        let stored = SqliteBackend::select_tasks(&connection, "", [])
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(stored.tags, task.tags);
+        assert_eq!(stored.depends_on, task.depends_on);
+    }
+
+    #[test]
+    fn writes_do_not_restore_tasks_deleted_by_another_instance() {
+        let root_dir =
+            std::env::temp_dir().join(format!("terminal-todo-sqlite-{}", std::process::id()));
+        fs::create_dir_all(&root_dir).unwrap();
+        let open = || {
+            let mut connection = Connection::open(root_dir.join("data.db")).unwrap();
+            connection.execute_batch(SCHEMA).unwrap();
+            SqliteBackend::migrate_schema(&mut connection).unwrap();
+            StorageHandler::with_backend(Box::new(SqliteBackend {
+                connection: Some(connection),
+            }))
+        };
+        let mut first = open();
+        let id = first
+            .add_single_task(Task::new("a".to_string(), None))
+            .unwrap();
+        let mut second = open();
+        let mut task = second.get_task_by_id(id).unwrap().unwrap();
+        first.delete_tasks(&[id]).unwrap();
+        task.title = "renamed".to_string();
+        second.write_task_data(vec![task]).unwrap();
+        let tasks = open().get_all_tasks().unwrap();
+        fs::remove_dir_all(&root_dir).unwrap();
+        assert!(tasks.is_empty());
+    }
+}
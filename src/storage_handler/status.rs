@@ -0,0 +1,110 @@
+use crate::error::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::env;
+
+/// The status of a task. Done and cancelled
+/// tasks are closed, all other tasks are open
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum Status {
+    #[default]
+    Todo,
+    InProgress,
+    Blocked,
+    Waiting,
+    Done,
+    Cancelled,
+}
+
+impl Status {
+    /// All statuses in the order they are offered for selection
+    pub const ALL: [Status; 6] = [
+        Status::Todo,
+        Status::InProgress,
+        Status::Blocked,
+        Status::Waiting,
+        Status::Done,
+        Status::Cancelled,
+    ];
+
+    /// Gets the name of the status that is
+    /// used for input, output and storage
+    pub fn name(&self) -> &'static str {
+        match self {
+            Status::Todo => "todo",
+            Status::InProgress => "in-progress",
+            Status::Blocked => "blocked",
+            Status::Waiting => "waiting",
+            Status::Done => "done",
+            Status::Cancelled => "cancelled",
+        }
+    }
+
+    /// Gets the symbol that is shown next to the task. The returned
+    /// strings are symbols and may not be supported by every terminal / cmd
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Status::Todo => "❌",
+            Status::InProgress => "🔄",
+            Status::Blocked => "⛔",
+            Status::Waiting => "⏳",
+            Status::Done => "✅",
+            Status::Cancelled => "🚫",
+        }
+    }
+
+    /// Checks if no more work is expected on the task
+    pub fn is_closed(&self) -> bool {
+        matches!(self, Status::Done | Status::Cancelled)
+    }
+
+    /// Parses the status by its name
+    pub fn parse(input: &str) -> AppResult<Status> {
+        Status::ALL
+            .into_iter()
+            .find(|status| status.name() == input.trim().to_lowercase())
+            .ok_or_else(|| {
+                AppError::Validation(format!(
+                    "{} is not a valid status. Use one of todo, in-progress, blocked, waiting, done, cancelled",
+                    input
+                ))
+            })
+    }
+}
+
+/// Defines the order in which the status of a task is cycled
+/// in the list. It can be configured with the
+/// TERMINAL_TODO_WORKFLOW environment variable, e.g. todo,in-progress,done
+pub struct Workflow {
+    statuses: Vec<Status>,
+}
+
+impl Workflow {
+    /// Reads the workflow from the environment. If it is
+    /// not set, tasks cycle through todo, in-progress and done
+    pub fn from_env() -> AppResult<Workflow> {
+        let statuses = match env::var("TERMINAL_TODO_WORKFLOW") {
+            Ok(value) => value
+                .split(',')
+                .filter(|name| !name.trim().is_empty())
+                .map(Status::parse)
+                .collect::<AppResult<Vec<Status>>>()?,
+            Err(_) => vec![Status::Todo, Status::InProgress, Status::Done],
+        };
+        if statuses.is_empty() {
+            return Err(AppError::Validation(
+                "The workflow must contain at least one status".to_string(),
+            ));
+        }
+        Ok(Workflow { statuses })
+    }
+
+    /// Gets the status that follows the given one. Statuses that
+    /// are not part of the workflow continue with the first status
+    pub fn next(&self, status: Status) -> Status {
+        match self.statuses.iter().position(|other| *other == status) {
+            Some(index) => self.statuses[(index + 1) % self.statuses.len()],
+            None => self.statuses[0],
+        }
+    }
+}
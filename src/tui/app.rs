@@ -0,0 +1,416 @@
+use crate::commands::delete_command::{ChildAction, DeleteCommand};
+use crate::commands::task_format::get_blocker_warning;
+use crate::commands::task_search::contains_text;
+use crate::commands::task_tree::{build_tree, TreeEntry};
+use crate::error::{AppError, AppResult};
+use crate::inputs::due_date;
+use crate::inputs::tags;
+use crate::storage_handler::status::{Status, Workflow};
+use crate::storage_handler::{StorageHandler, Task};
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+/// Defines which pane receives the navigation keys
+#[derive(PartialEq)]
+pub enum Focus {
+    Groups,
+    Tasks,
+}
+
+/// Defines what the keys are used for at the moment
+pub enum Mode {
+    Normal,
+    Add,
+    Filter,
+    ConfirmDelete,
+    Move(usize),
+}
+
+/// Defines a single entry of the group sidebar
+#[derive(Clone, PartialEq)]
+pub enum GroupEntry {
+    All,
+    Ungrouped,
+    Named(String),
+}
+
+impl GroupEntry {
+    /// Gets the name that is shown in the sidebar
+    pub fn name(&self) -> &str {
+        match self {
+            GroupEntry::All => "All tasks",
+            GroupEntry::Ungrouped => "No group",
+            GroupEntry::Named(name) => name,
+        }
+    }
+
+    /// Checks if the task is shown when the entry is selected
+    pub fn contains(&self, task: &Task) -> bool {
+        match self {
+            GroupEntry::All => true,
+            GroupEntry::Ungrouped => task.group.is_none(),
+            GroupEntry::Named(name) => task.group.as_ref() == Some(name),
+        }
+    }
+}
+
+/// Defines the state of the full-screen interface. All changes
+/// are saved through the storage handler right away
+pub struct App {
+    storage_handler: StorageHandler,
+    pub all_tasks: Vec<Task>,
+    pub groups: Vec<GroupEntry>,
+    pub entries: Vec<TreeEntry>,
+    pub group_index: usize,
+    pub task_index: usize,
+    pub focus: Focus,
+    pub mode: Mode,
+    pub input: String,
+    pub filter: String,
+    pub message: Option<String>,
+    pub edit_request: Option<u64>,
+    pub running: bool,
+}
+
+impl App {
+    /// Creates the interface state and reads
+    /// the groups and tasks for the first time
+    pub fn new() -> AppResult<App> {
+        App::with_storage(StorageHandler::new())
+    }
+
+    /// Creates the interface state that uses the given storage
+    /// handler and reads the groups and tasks for the first time
+    pub fn with_storage(storage_handler: StorageHandler) -> AppResult<App> {
+        let mut app = App {
+            storage_handler,
+            all_tasks: vec![],
+            groups: vec![],
+            entries: vec![],
+            group_index: 0,
+            task_index: 0,
+            focus: Focus::Tasks,
+            mode: Mode::Normal,
+            input: String::new(),
+            filter: String::new(),
+            message: None,
+            edit_request: None,
+            running: true,
+        };
+        app.refresh()?;
+        Ok(app)
+    }
+
+    /// Reads the groups and tasks again, so that the changes of
+    /// other instances are shown. The selected task is kept if it still exists
+    pub fn refresh(&mut self) -> AppResult<()> {
+        let selected = self.selected_task().map(|task| task.id);
+        self.storage_handler.reload();
+        self.all_tasks = self.storage_handler.get_all_tasks()?;
+        let mut groups = vec![GroupEntry::All, GroupEntry::Ungrouped];
+        groups.extend(
+            self.storage_handler
+                .get_all_groups()?
+                .into_iter()
+                .map(GroupEntry::Named),
+        );
+        self.groups = groups;
+        self.group_index = self.group_index.min(self.groups.len() - 1);
+        self.update_entries();
+        if let Some(index) = self
+            .entries
+            .iter()
+            .position(|entry| Some(entry.task.id) == selected)
+        {
+            self.task_index = index;
+        }
+        Ok(())
+    }
+
+    /// Builds the task tree of the selected group. Tasks that do not
+    /// match the filter are left out, the others are sorted by priority
+    fn update_entries(&mut self) {
+        let group = &self.groups[self.group_index];
+        let mut tasks = self
+            .all_tasks
+            .iter()
+            .filter(|task| group.contains(task) && contains_text(task, &self.filter))
+            .cloned()
+            .collect::<Vec<Task>>();
+        tasks.sort_by_key(|task| Reverse(task.priority));
+        self.entries = build_tree(tasks, &HashSet::new());
+        self.task_index = self.task_index.min(self.entries.len().saturating_sub(1));
+    }
+
+    /// Gets the task that is selected in the task pane
+    pub fn selected_task(&self) -> Option<&Task> {
+        self.entries.get(self.task_index).map(|entry| &entry.task)
+    }
+
+    /// Gets the names of all groups a task can be moved into.
+    /// The first entry removes the task from its group
+    pub fn move_targets(&self) -> Vec<String> {
+        let mut targets = vec!["❌ no group".to_string()];
+        for group in self.groups.iter() {
+            if let GroupEntry::Named(name) = group {
+                targets.push(name.clone());
+            }
+        }
+        targets
+    }
+
+    /// Handles a pressed key depending on the current mode. Errors
+    /// are shown in the status line instead of closing the interface
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        let result = match self.mode {
+            Mode::Normal => self.handle_normal_key(key),
+            Mode::Add => self.handle_add_key(key),
+            Mode::Filter => self.handle_filter_key(key),
+            Mode::ConfirmDelete => self.handle_delete_key(key),
+            Mode::Move(index) => self.handle_move_key(key, index),
+        };
+        if let Err(error) = result {
+            self.mode = Mode::Normal;
+            self.message = Some(match error {
+                AppError::Cancelled => "Cancelled".to_string(),
+                error => format!("ERROR: {}", error),
+            });
+        }
+    }
+
+    /// Handles the navigation and the keys that start an action
+    fn handle_normal_key(&mut self, key: KeyEvent) -> AppResult<()> {
+        self.message = None;
+        match key.code {
+            KeyCode::Char('q') => self.running = false,
+            KeyCode::Esc if !self.filter.is_empty() => {
+                self.filter.clear();
+                self.update_entries();
+            }
+            KeyCode::Esc => self.running = false,
+            KeyCode::Tab
+            | KeyCode::Left
+            | KeyCode::Right
+            | KeyCode::Char('h')
+            | KeyCode::Char('l') => {
+                self.focus = match self.focus {
+                    Focus::Groups => Focus::Tasks,
+                    Focus::Tasks => Focus::Groups,
+                };
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.select_offset(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.select_offset(1),
+            KeyCode::Char('a') => {
+                self.input.clear();
+                self.mode = Mode::Add;
+            }
+            KeyCode::Char('/') => {
+                self.input = self.filter.clone();
+                self.mode = Mode::Filter;
+            }
+            KeyCode::Char('r') => self.refresh()?,
+            KeyCode::Char(' ') | KeyCode::Char('x') => self.toggle_task()?,
+            KeyCode::Char('s') => self.advance_status()?,
+            KeyCode::Char('e') => self.edit_request = Some(self.require_task()?.id),
+            KeyCode::Char('d') => {
+                self.require_task()?;
+                self.mode = Mode::ConfirmDelete;
+            }
+            KeyCode::Char('m') => {
+                let group = self.require_task()?.group.clone();
+                let index = self
+                    .move_targets()
+                    .iter()
+                    .position(|target| Some(target) == group.as_ref());
+                self.mode = Mode::Move(index.unwrap_or(0));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Moves the selection of the focused pane by the offset
+    fn select_offset(&mut self, offset: isize) {
+        match self.focus {
+            Focus::Groups => {
+                self.group_index = step(self.group_index, offset, self.groups.len());
+                self.task_index = 0;
+                self.update_entries();
+            }
+            Focus::Tasks => self.task_index = step(self.task_index, offset, self.entries.len()),
+        }
+    }
+
+    /// Gets the selected task. If there is none, a not found error is returned
+    fn require_task(&self) -> AppResult<&Task> {
+        self.selected_task()
+            .ok_or_else(|| AppError::NotFound("No task selected".to_string()))
+    }
+
+    /// Handles the input of the title of a new task. Words starting with a +
+    /// are added as tags. The task is added to the selected group
+    fn handle_add_key(&mut self, key: KeyEvent) -> AppResult<()> {
+        if key.code != KeyCode::Enter {
+            if !edit_input(&mut self.input, key) {
+                self.mode = Mode::Normal;
+            }
+            return Ok(());
+        }
+        self.mode = Mode::Normal;
+        let (title, tags) = tags::extract_tags(&self.input);
+        if title.is_empty() {
+            return Err(AppError::Validation(
+                "The title must not be empty".to_string(),
+            ));
+        }
+        let group = match &self.groups[self.group_index] {
+            GroupEntry::Named(name) => Some(name.clone()),
+            _ => None,
+        };
+        let mut task = Task::new(title, group);
+        task.tags = tags;
+        let id = self.storage_handler.add_single_task(task)?;
+        self.message = Some(format!("Added the task with ID {}", id));
+        self.refresh()?;
+        if let Some(index) = self.entries.iter().position(|entry| entry.task.id == id) {
+            self.task_index = index;
+        }
+        Ok(())
+    }
+
+    /// Handles the input of the filter. The task pane is
+    /// updated with every key, Esc removes the filter
+    fn handle_filter_key(&mut self, key: KeyEvent) -> AppResult<()> {
+        match key.code {
+            KeyCode::Enter => self.mode = Mode::Normal,
+            _ if !edit_input(&mut self.input, key) => {
+                self.input.clear();
+                self.mode = Mode::Normal;
+            }
+            _ => {}
+        }
+        self.filter = self.input.clone();
+        self.task_index = 0;
+        self.update_entries();
+        Ok(())
+    }
+
+    /// Checks if the selected task has subtasks
+    pub fn selected_has_children(&self) -> bool {
+        self.selected_task().is_some_and(|task| {
+            self.all_tasks
+                .iter()
+                .any(|other| other.parent == Some(task.id))
+        })
+    }
+
+    /// Deletes the selected task after it has been confirmed with y. Tasks
+    /// with subtasks are confirmed with c to delete the subtasks as well
+    /// or with u to move the subtasks one level up
+    fn handle_delete_key(&mut self, key: KeyEvent) -> AppResult<()> {
+        self.mode = Mode::Normal;
+        let action = match (self.selected_has_children(), key.code) {
+            (false, KeyCode::Char('y')) | (true, KeyCode::Char('c')) => ChildAction::Cascade,
+            (true, KeyCode::Char('u')) => ChildAction::Reparent,
+            _ => return Err(AppError::Cancelled),
+        };
+        let task = self.require_task()?.clone();
+        DeleteCommand::new().delete_with_action(&[task.id], action)?;
+        self.message = Some(format!("Deleted {}", task.title));
+        self.refresh()
+    }
+
+    /// Handles the selection of the group the
+    /// selected task is moved into
+    fn handle_move_key(&mut self, key: KeyEvent, index: usize) -> AppResult<()> {
+        let targets = self.move_targets();
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.mode = Mode::Move(step(index, -1, targets.len()))
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.mode = Mode::Move(step(index, 1, targets.len()))
+            }
+            KeyCode::Enter => {
+                self.mode = Mode::Normal;
+                let group = match index {
+                    0 => None,
+                    _ => Some(targets[index].clone()),
+                };
+                let task = Task {
+                    group,
+                    ..self.require_task()?.clone()
+                };
+                let target = task.group.as_deref().unwrap_or("no group");
+                self.message = Some(format!("Moved {} to {}", task.title, target));
+                self.storage_handler.write_task_data(vec![task])?;
+                self.refresh()?;
+            }
+            _ => return Err(AppError::Cancelled),
+        }
+        Ok(())
+    }
+
+    /// Marks the selected task as done. Done tasks are opened again
+    fn toggle_task(&mut self) -> AppResult<()> {
+        let task = self.require_task()?;
+        let status = match task.status {
+            Status::Done => Status::Todo,
+            _ => Status::Done,
+        };
+        self.save_status(status)
+    }
+
+    /// Moves the selected task to the next status of the workflow
+    fn advance_status(&mut self) -> AppResult<()> {
+        let status = Workflow::from_env()?.next(self.require_task()?.status);
+        self.save_status(status)
+    }
+
+    /// Saves the selected task with the new status. Tasks with open subtasks
+    /// cannot be done and completing a recurring task adds its next occurrence
+    fn save_status(&mut self, status: Status) -> AppResult<()> {
+        let task = Task {
+            status,
+            ..self.require_task()?.clone()
+        };
+        self.storage_handler
+            .validate_finished(std::slice::from_ref(&task))?;
+        let warning = get_blocker_warning(&task, &self.all_tasks);
+        let occurrences = self
+            .storage_handler
+            .write_completed_tasks(vec![task.clone()], due_date::today())?;
+        self.message = Some(match (occurrences.first(), warning) {
+            (Some(next), _) => format!(
+                "Added the next occurrence of {} with ID {}",
+                next.title, next.id
+            ),
+            (None, Some(warning)) => format!("WARNING: {}", warning),
+            (None, None) => format!("{} is {}", task.title, status.name()),
+        });
+        self.refresh()
+    }
+}
+
+/// Moves the index by the offset and keeps it inside of the length
+fn step(index: usize, offset: isize, len: usize) -> usize {
+    match len {
+        0 => 0,
+        _ => index.saturating_add_signed(offset).min(len - 1),
+    }
+}
+
+/// Applies the key to the text input. Returns
+/// false if the input has been cancelled with Esc
+fn edit_input(input: &mut String, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Char(c) => input.push(c),
+        KeyCode::Backspace => {
+            input.pop();
+        }
+        KeyCode::Esc => return false,
+        _ => {}
+    }
+    true
+}
@@ -0,0 +1,305 @@
+use crate::commands::task_format::get_blocker_warning;
+use crate::error::{AppError, AppResult};
+use crate::inputs::due_date;
+use crate::inputs::tags::TagFilter;
+use crate::storage_handler::status::Status;
+use crate::storage_handler::{StorageHandler, Task};
+use crate::tui::ui::task_spans;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+use std::cmp::Reverse;
+
+/// The keys that are shown at the bottom of the board
+const HELP: &str = "←/→ column  ↑/↓ card  shift+←/→ or </> move card  r reload  q quit";
+
+/// Defines by which field the tasks are split into columns
+#[derive(Clone, Copy, PartialEq)]
+pub enum BoardLayout {
+    Status,
+    Group,
+}
+
+impl BoardLayout {
+    /// Parses the layout by its name
+    pub fn parse(input: &str) -> AppResult<BoardLayout> {
+        match input.trim().to_lowercase().as_str() {
+            "status" => Ok(BoardLayout::Status),
+            "group" => Ok(BoardLayout::Group),
+            _ => Err(AppError::Validation(format!(
+                "Cannot show the board by {}. Use status or group",
+                input
+            ))),
+        }
+    }
+}
+
+/// Defines the value all cards of a column share
+#[derive(Clone, PartialEq)]
+pub enum ColumnKey {
+    Status(Status),
+    Group(Option<String>),
+}
+
+/// Defines a single column of the board with its cards
+pub struct Column {
+    pub key: ColumnKey,
+    pub tasks: Vec<Task>,
+}
+
+impl Column {
+    /// Gets the title of the column
+    pub fn title(&self) -> String {
+        match &self.key {
+            ColumnKey::Status(status) => format!("{} {}", status.symbol(), status.name()),
+            ColumnKey::Group(Some(group)) => group.clone(),
+            ColumnKey::Group(None) => "No group".to_string(),
+        }
+    }
+}
+
+/// Defines the state of the board. Moving a card
+/// saves the task through the storage handler right away
+pub struct Board {
+    storage_handler: StorageHandler,
+    layout: BoardLayout,
+    filter: TagFilter,
+    pub all_tasks: Vec<Task>,
+    pub columns: Vec<Column>,
+    pub column_index: usize,
+    pub card_index: usize,
+    pub message: Option<String>,
+    pub running: bool,
+}
+
+impl Board {
+    /// Creates the board and reads the tasks for the first time.
+    /// Only tasks that match the tag filter are shown
+    pub fn new(layout: BoardLayout, filter: TagFilter) -> AppResult<Board> {
+        Board::with_storage(layout, filter, StorageHandler::new())
+    }
+
+    /// Creates the board that uses the given storage handler
+    /// and reads the tasks for the first time
+    pub fn with_storage(
+        layout: BoardLayout,
+        filter: TagFilter,
+        storage_handler: StorageHandler,
+    ) -> AppResult<Board> {
+        let mut board = Board {
+            storage_handler,
+            layout,
+            filter,
+            all_tasks: vec![],
+            columns: vec![],
+            column_index: 0,
+            card_index: 0,
+            message: None,
+            running: true,
+        };
+        board.refresh()?;
+        Ok(board)
+    }
+
+    /// Reads the tasks again and splits them into the columns.
+    /// The selected card is kept if it still exists
+    pub fn refresh(&mut self) -> AppResult<()> {
+        let selected = self.selected_task().map(|task| task.id);
+        self.storage_handler.reload();
+        let keys = match self.layout {
+            BoardLayout::Status => Status::ALL
+                .into_iter()
+                .map(ColumnKey::Status)
+                .collect::<Vec<ColumnKey>>(),
+            BoardLayout::Group => {
+                let mut keys = vec![ColumnKey::Group(None)];
+                keys.extend(
+                    self.storage_handler
+                        .get_all_groups()?
+                        .into_iter()
+                        .map(|group| ColumnKey::Group(Some(group))),
+                );
+                keys
+            }
+        };
+        self.all_tasks = self.storage_handler.get_all_tasks()?;
+        let mut tasks = self.all_tasks.clone();
+        tasks.retain(|task| self.filter.matches(task));
+        tasks.sort_by_key(|task| Reverse(task.priority));
+        self.columns = keys
+            .into_iter()
+            .map(|key| Column {
+                tasks: tasks
+                    .iter()
+                    .filter(|task| column_key(task, self.layout) == key)
+                    .cloned()
+                    .collect(),
+                key,
+            })
+            .collect();
+        self.column_index = self.column_index.min(self.columns.len() - 1);
+        for (column_index, column) in self.columns.iter().enumerate() {
+            if let Some(card_index) = column
+                .tasks
+                .iter()
+                .position(|task| Some(task.id) == selected)
+            {
+                (self.column_index, self.card_index) = (column_index, card_index);
+            }
+        }
+        self.clamp_card_index();
+        Ok(())
+    }
+
+    /// Keeps the selected card inside of the selected column
+    fn clamp_card_index(&mut self) {
+        let len = self.columns[self.column_index].tasks.len();
+        self.card_index = self.card_index.min(len.saturating_sub(1));
+    }
+
+    /// Gets the task of the selected card
+    pub fn selected_task(&self) -> Option<&Task> {
+        self.columns
+            .get(self.column_index)
+            .and_then(|column| column.tasks.get(self.card_index))
+    }
+
+    /// Handles a pressed key. Errors are shown in the
+    /// status line instead of closing the board
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        self.message = None;
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        let result = match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => {
+                self.running = false;
+                Ok(())
+            }
+            KeyCode::Char('r') => self.refresh(),
+            KeyCode::Left | KeyCode::Right if shift => self.move_card(key.code == KeyCode::Right),
+            KeyCode::Char('<') | KeyCode::Char('H') => self.move_card(false),
+            KeyCode::Char('>') | KeyCode::Char('L') => self.move_card(true),
+            KeyCode::Left | KeyCode::Char('h') => self.select_column(-1),
+            KeyCode::Right | KeyCode::Char('l') => self.select_column(1),
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.card_index = self.card_index.saturating_sub(1);
+                Ok(())
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.card_index += 1;
+                self.clamp_card_index();
+                Ok(())
+            }
+            _ => Ok(()),
+        };
+        if let Err(error) = result {
+            self.message = Some(format!("ERROR: {}", error));
+        }
+    }
+
+    /// Selects the column next to the selected one
+    fn select_column(&mut self, offset: isize) -> AppResult<()> {
+        self.column_index = self
+            .column_index
+            .saturating_add_signed(offset)
+            .min(self.columns.len() - 1);
+        self.clamp_card_index();
+        Ok(())
+    }
+
+    /// Moves the selected card into the previous or next column and saves it.
+    /// Tasks with open subtasks cannot be done, tasks with open blockers are
+    /// warned about and completing a recurring task adds its next occurrence
+    fn move_card(&mut self, forward: bool) -> AppResult<()> {
+        let task = self
+            .selected_task()
+            .cloned()
+            .ok_or_else(|| AppError::NotFound("No task selected".to_string()))?;
+        let target = match forward {
+            true => self.column_index + 1,
+            false => self.column_index.wrapping_sub(1),
+        };
+        let (key, title) = match self.columns.get(target) {
+            Some(column) => (column.key.clone(), column.title()),
+            None => return Ok(()),
+        };
+        let task = match key {
+            ColumnKey::Status(status) => Task { status, ..task },
+            ColumnKey::Group(group) => Task { group, ..task },
+        };
+        self.storage_handler
+            .validate_finished(std::slice::from_ref(&task))?;
+        let warning = get_blocker_warning(&task, &self.all_tasks);
+        let occurrences = self
+            .storage_handler
+            .write_completed_tasks(vec![task.clone()], due_date::today())?;
+        self.message = Some(match occurrences.first() {
+            Some(next) => format!(
+                "Added the next occurrence of {} with ID {}",
+                next.title, next.id
+            ),
+            None => match warning {
+                Some(warning) => format!("WARNING: {}", warning),
+                None => format!("Moved {} to {}", task.title, title),
+            },
+        });
+        self.refresh()
+    }
+}
+
+/// Gets the key of the column the task belongs to
+pub fn column_key(task: &Task, layout: BoardLayout) -> ColumnKey {
+    match layout {
+        BoardLayout::Status => ColumnKey::Status(task.status),
+        BoardLayout::Group => ColumnKey::Group(task.group.clone()),
+    }
+}
+
+/// Formats the task as a card with its ID in front of the task line
+fn card_line<'a>(task: &Task, tasks: &[Task]) -> Line<'a> {
+    let mut spans = vec![Span::raw(format!("{:>3} ", task.id)).dark_gray()];
+    spans.extend(task_spans(task, tasks));
+    Line::from(spans)
+}
+
+/// Draws all columns of the board next to each
+/// other with the status line below them
+pub fn draw(frame: &mut Frame, board: &Board) {
+    let [main, status, help] = Layout::vertical([
+        Constraint::Min(3),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let areas = Layout::horizontal(board.columns.iter().map(|_| Constraint::Fill(1))).split(main);
+    for (index, (column, area)) in board.columns.iter().zip(areas.iter()).enumerate() {
+        let selected = index == board.column_index;
+        let mut block =
+            Block::bordered().title(format!("{} ({})", column.title(), column.tasks.len()));
+        if selected {
+            block = block.border_style(Style::new().fg(Color::Cyan));
+        }
+        let items = column
+            .tasks
+            .iter()
+            .map(|task| ListItem::new(card_line(task, &board.all_tasks)));
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        let mut state =
+            ListState::default().with_selected(match selected && !column.tasks.is_empty() {
+                true => Some(board.card_index),
+                false => None,
+            });
+        frame.render_stateful_widget(list, *area, &mut state);
+    }
+    let message = match &board.message {
+        Some(message) if message.starts_with("ERROR") => Line::from(message.as_str()).red(),
+        Some(message) => Line::from(message.as_str()),
+        None => Line::default(),
+    };
+    frame.render_widget(Paragraph::new(message), status);
+    frame.render_widget(Paragraph::new(HELP).dark_gray(), help);
+}
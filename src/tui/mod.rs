@@ -0,0 +1,97 @@
+use crate::commands::command_trait::Command;
+use crate::commands::edit_command::EditCommand;
+use crate::error::{AppError, AppResult};
+use crate::tui::app::App;
+use crate::tui::board::Board;
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use std::io;
+use std::time::Duration;
+
+pub mod app;
+pub mod board;
+pub mod ui;
+
+/// The time after which the data is read again
+/// if no key has been pressed
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Opens the full-screen interface and keeps it open until
+/// it is closed. The terminal is restored in any case
+pub fn run() -> AppResult<()> {
+    let mut app = App::new()?;
+    let mut terminal = ratatui::try_init()?;
+    let result = run_loop(&mut terminal, &mut app);
+    ratatui::restore();
+    result
+}
+
+/// Draws the interface and handles the keys until the app is closed.
+/// Without any key, the data is refreshed to show changes of other instances
+fn run_loop(terminal: &mut ratatui::DefaultTerminal, app: &mut App) -> AppResult<()> {
+    while app.running {
+        terminal.draw(|frame| ui::draw(frame, app))?;
+        if !event::poll(REFRESH_INTERVAL)? {
+            if let Err(error) = app.refresh() {
+                app.message = Some(format!("ERROR: {}", error));
+            }
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press {
+                app.handle_key(key);
+            }
+        }
+        if let Some(id) = app.edit_request.take() {
+            *terminal = suspend_for_edit(id)?;
+            app.message = app.refresh().err().map(|error| format!("ERROR: {}", error));
+        }
+    }
+    Ok(())
+}
+
+/// Opens the board and keeps it open until it is closed.
+/// The terminal is restored in any case
+pub fn run_board(board: &mut Board) -> AppResult<()> {
+    let mut terminal = ratatui::try_init()?;
+    let result = run_board_loop(&mut terminal, board);
+    ratatui::restore();
+    result
+}
+
+/// Draws the board and handles the keys until the board is closed.
+/// Without any key, the data is refreshed to show changes of other instances
+fn run_board_loop(terminal: &mut ratatui::DefaultTerminal, board: &mut Board) -> AppResult<()> {
+    while board.running {
+        terminal.draw(|frame| board::draw(frame, board))?;
+        if !event::poll(REFRESH_INTERVAL)? {
+            if let Err(error) = board.refresh() {
+                board.message = Some(format!("ERROR: {}", error));
+            }
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press {
+                board.handle_key(key);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Leaves the full-screen interface for the field prompts of the edit
+/// command. The interface is opened again after the task has been saved
+fn suspend_for_edit(id: u64) -> io::Result<ratatui::DefaultTerminal> {
+    ratatui::restore();
+    match EditCommand::new()
+        .with_arguments(vec![id.to_string()])
+        .execute()
+    {
+        Ok(()) | Err(AppError::Cancelled) => {}
+        Err(error) => {
+            eprintln!("ERROR: {}", error);
+            eprintln!("Press enter to continue");
+            io::stdin().read_line(&mut String::new())?;
+        }
+    }
+    ratatui::try_init()
+}
@@ -0,0 +1,228 @@
+use crate::commands::task_format::{format_time, task_segments, Highlight};
+use crate::storage_handler::Task;
+use crate::tui::app::{App, Focus, Mode};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Clear, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::Frame;
+
+/// The keys that are shown at the bottom of the interface
+const HELP: &str = "a add  space toggle  s status  e edit  d delete  m move  / filter  r reload  tab switch  q quit";
+
+/// Draws the whole interface with the group sidebar,
+/// the task pane, the detail pane and the status line
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [main, status, help] = Layout::vertical([
+        Constraint::Min(3),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [groups, tasks, details] = Layout::horizontal([
+        Constraint::Length(24),
+        Constraint::Percentage(50),
+        Constraint::Min(30),
+    ])
+    .areas(main);
+    draw_groups(frame, app, groups);
+    draw_tasks(frame, app, tasks);
+    draw_details(frame, app, details);
+    frame.render_widget(Paragraph::new(status_line(app)), status);
+    frame.render_widget(Paragraph::new(HELP).dark_gray(), help);
+    if let Mode::Move(index) = app.mode {
+        draw_move_popup(frame, app, index);
+    }
+}
+
+/// Creates the block around a pane. The focused pane is highlighted
+fn pane_block(title: &str, focused: bool) -> Block<'_> {
+    let block = Block::bordered().title(title);
+    match focused {
+        true => block.border_style(Style::new().fg(Color::Cyan)),
+        false => block,
+    }
+}
+
+/// Draws the sidebar with all groups and the number of their open tasks
+fn draw_groups(frame: &mut Frame, app: &App, area: Rect) {
+    let items = app
+        .groups
+        .iter()
+        .map(|group| {
+            let open = app
+                .all_tasks
+                .iter()
+                .filter(|task| !task.is_closed() && group.contains(task))
+                .count();
+            ListItem::new(format!("{} ({})", group.name(), open))
+        })
+        .collect::<Vec<ListItem>>();
+    let list = List::new(items)
+        .block(pane_block("Groups", app.focus == Focus::Groups))
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default().with_selected(Some(app.group_index));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+/// Gets the styled spans of the task with its status, priority,
+/// title, due date, tags, blockers, recurrence and notes marker
+pub fn task_spans(task: &Task, tasks: &[Task]) -> Vec<Span<'static>> {
+    task_segments(task, tasks)
+        .into_iter()
+        .map(|(text, highlight)| {
+            let span = Span::raw(text);
+            match highlight {
+                Highlight::Plain => span,
+                Highlight::Dimmed => span.dark_gray(),
+                Highlight::Tag => span.cyan(),
+                Highlight::Warning => span.yellow(),
+                Highlight::Alert => span.red().bold(),
+            }
+        })
+        .collect()
+}
+
+/// Formats the task as an indented line of the task pane
+fn task_line<'a>(app: &App, task: &Task, depth: usize) -> Line<'a> {
+    let mut spans = vec![Span::raw("  ".repeat(depth))];
+    spans.extend(task_spans(task, &app.all_tasks));
+    Line::from(spans)
+}
+
+/// Draws the tree of tasks of the selected group
+fn draw_tasks(frame: &mut Frame, app: &App, area: Rect) {
+    let items = app
+        .entries
+        .iter()
+        .map(|entry| ListItem::new(task_line(app, &entry.task, entry.depth)))
+        .collect::<Vec<ListItem>>();
+    let title = match app.filter.is_empty() {
+        true => app.groups[app.group_index].name().to_string(),
+        false => format!("{} /{}", app.groups[app.group_index].name(), app.filter),
+    };
+    let list = List::new(items)
+        .block(pane_block(&title, app.focus == Focus::Tasks))
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default().with_selected(app.selected_task().map(|_| app.task_index));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+/// Draws all details and the notes of the selected task
+fn draw_details(frame: &mut Frame, app: &App, area: Rect) {
+    let block = pane_block("Details", false);
+    let task = match app.selected_task() {
+        Some(task) => task,
+        None => {
+            frame.render_widget(Paragraph::new("No task selected").block(block), area);
+            return;
+        }
+    };
+    let mut lines = vec![
+        Line::from(task.title.clone()).bold(),
+        Line::from(format!("ID:       {}", task.id)),
+        Line::from(format!(
+            "STATUS:   {} {}",
+            task.status.symbol(),
+            task.status.name()
+        )),
+        Line::from(format!("PRIORITY: {}", task.priority.name())),
+    ];
+    if let Some(group) = &task.group {
+        lines.push(Line::from(format!("GROUP:    {}", group)));
+    }
+    if let Some(parent) = task.parent {
+        lines.push(Line::from(format!("PARENT:   {}", parent)));
+    }
+    if let Some(due) = task.due {
+        lines.push(Line::from(format!("DUE:      {}", due)));
+    }
+    if let Some(recurrence) = &task.recurrence {
+        lines.push(Line::from(format!("REPEATS:  {}", recurrence)));
+    }
+    if !task.depends_on.is_empty() {
+        let ids = task
+            .depends_on
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<String>>();
+        lines.push(Line::from(format!("DEPENDS:  {}", ids.join(", "))));
+    }
+    lines.push(Line::from(format!(
+        "CREATED:  {}",
+        format_time(task.created_at)
+    )));
+    lines.push(Line::from(format!(
+        "UPDATED:  {}",
+        format_time(task.updated_at)
+    )));
+    if task.completed_at.is_some() {
+        lines.push(Line::from(format!(
+            "COMPLETED:{}",
+            format_time(task.completed_at)
+        )));
+    }
+    if let Some(notes) = &task.notes {
+        lines.push(Line::default());
+        lines.extend(notes.lines().map(|line| Line::from(line.to_string())));
+    }
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+/// Gets the status line. It shows the current input or
+/// confirmation, otherwise the result of the last action
+fn status_line(app: &App) -> Line<'_> {
+    match app.mode {
+        Mode::Add => Line::from(format!("New task: {}█", app.input)),
+        Mode::Filter => Line::from(format!("/{}█", app.input)),
+        Mode::ConfirmDelete => {
+            let title = app
+                .selected_task()
+                .map(|task| task.title.clone())
+                .unwrap_or_default();
+            match app.selected_has_children() {
+                false => Line::from(format!("Delete {}? (y/n)", title)).yellow(),
+                true => Line::from(format!(
+                    "Delete {}: c deletes the subtasks too, u moves them one level up, n cancels",
+                    title
+                ))
+                .yellow(),
+            }
+        }
+        _ => match &app.message {
+            Some(message) if message.starts_with("ERROR") => Line::from(message.as_str()).red(),
+            Some(message) => Line::from(message.as_str()),
+            None => Line::default(),
+        },
+    }
+}
+
+/// Draws the list of groups the selected task
+/// can be moved into above the other panes
+fn draw_move_popup(frame: &mut Frame, app: &App, index: usize) {
+    let targets = app.move_targets();
+    let area = frame.area();
+    let height = (targets.len() as u16 + 2).min(area.height);
+    let width = 30.min(area.width);
+    let popup = Rect::new(
+        (area.width - width) / 2,
+        (area.height - height) / 2,
+        width,
+        height,
+    );
+    let list = List::new(targets)
+        .block(
+            Block::bordered()
+                .title("Move to")
+                .border_style(Style::new().fg(Color::Cyan)),
+        )
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default().with_selected(Some(index));
+    frame.render_widget(Clear, popup);
+    frame.render_stateful_widget(list, popup, &mut state);
+}